@@ -1,183 +1,1277 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::LazyLock;
+use std::time::Duration;
 
-use clap::{Parser, Subcommand};
-use pdftool_core::{compress_pdf, convert_pdf, extract_pages, extract_text, md_to_pdf, pdf_to_md, parse_page_range};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use indicatif::{ProgressBar, ProgressStyle};
+use pdftool_core::{
+    CancelToken, ColorMode, CompressOptions, DEFAULT_SPLIT_TEMPLATE, EnvironmentReport, PdfInfo,
+    PdfToolError, PdfVersion, PdfaLevel, Pipeline, Progress, ReorderMode, SplitMode, ToolRunner,
+    WatchOptions, check_environment, compress_pdf, compress_pdf_with, compress_to_size,
+    convert_color, convert_pdf, expand_inputs, extract_pages, extract_text, inspect_pdf,
+    is_linearized, linearize_pdf, md_to_pdf, merge_pdfs, page_count, parse_merge_input, pdf_to_md,
+    render_output_name, reorder_pages, resolve_page_range, resolve_page_sequence, rotate_pages,
+    run_batch, set_pdf_version, split_pdf, to_pdfa, watch_folder,
+};
 
 #[derive(Parser)]
-#[command(name = "pdftool", about = "CLI tool for PDF manipulation using Ghostscript")]
+#[command(
+    name = "pdftool",
+    about = "CLI tool for PDF manipulation using Ghostscript"
+)]
 struct Cli {
+    /// Print which external executables are used
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Stop an external tool that runs for longer than this many seconds
+    #[arg(long, global = true, value_name = "SECONDS")]
+    timeout: Option<u64>,
+
+    /// Number of input files processed at the same time
+    #[arg(short, long, global = true, default_value_t = 1, value_name = "N")]
+    jobs: usize,
+
+    /// PDF version of the files written: 1.3 to 1.7, or 2.0 (default: keep Ghostscript's choice)
+    #[arg(long, global = true, value_name = "VERSION")]
+    pdf_version: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// Output location for commands run on several inputs.
+#[derive(Args)]
+struct BatchOutput {
+    /// Directory for the output files (default: next to each input)
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+    /// Output file name template: {name} (input file name), {num} (position of the input);
+    /// the extension is added
+    #[arg(long, value_name = "TEMPLATE")]
+    name: Option<String>,
+}
+
+/// Compression settings: a preset plus optional overrides.
+#[derive(Args)]
+struct CompressFlags {
+    /// Compression quality: screen, ebook, printer, prepress
+    #[arg(short, long, default_value = "ebook")]
+    quality: String,
+    /// Downsample colour images to this resolution (DPI)
+    #[arg(long, value_name = "DPI")]
+    color_dpi: Option<u32>,
+    /// Downsample grayscale images to this resolution (DPI)
+    #[arg(long, value_name = "DPI")]
+    gray_dpi: Option<u32>,
+    /// Downsample black-and-white images to this resolution (DPI)
+    #[arg(long, value_name = "DPI")]
+    mono_dpi: Option<u32>,
+    /// JPEG quality for colour and grayscale images (1-100)
+    #[arg(long, value_name = "1-100")]
+    jpeg_quality: Option<u8>,
+    /// Only downsample images above this multiple of the target resolution (e.g. 1.5)
+    #[arg(long, value_name = "RATIO")]
+    downsample_threshold: Option<f32>,
+    /// Embed all fonts
+    #[arg(long, value_name = "BOOL")]
+    embed_fonts: Option<bool>,
+    /// Embed only the characters used from each font
+    #[arg(long, value_name = "BOOL")]
+    subset_fonts: Option<bool>,
+    /// Write a copy of the input when compressing does not make it smaller
+    #[arg(long)]
+    keep_original: bool,
+    /// Linearize the output ("fast web view") so browsers show the first page sooner
+    #[arg(long)]
+    linearize: bool,
+}
+
+impl CompressFlags {
+    fn options(&self) -> Result<CompressOptions, PdfToolError> {
+        Ok(CompressOptions {
+            color_dpi: self.color_dpi,
+            gray_dpi: self.gray_dpi,
+            mono_dpi: self.mono_dpi,
+            jpeg_quality: self.jpeg_quality,
+            downsample_threshold: self.downsample_threshold,
+            embed_fonts: self.embed_fonts,
+            subset_fonts: self.subset_fonts,
+            keep_original: self.keep_original,
+            linearize: self.linearize,
+            ..CompressOptions::from_preset(&self.quality)?
+        })
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
+    /// Show information about a PDF file
+    Info {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Print the information as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check which external tools are installed and which features work
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
     /// Extract specific pages from a PDF file
     Extract {
-        /// Input PDF file
-        input: PathBuf,
-        /// Page range (e.g. "2-5", "1,3,5", "1,3-5,8")
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Page range (e.g. "2-5", "1,3-5,8", "2-end", "-3-", "odd", "1-20,!7")
         #[arg(short, long)]
         pages: String,
-        /// Output PDF file (default: input_extracted.pdf)
+        /// Output PDF file, for a single input (default: input_extracted.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Rotate pages of a PDF file
+    Rotate {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Clockwise rotation in degrees: 90, 180, 270
+        #[arg(short, long)]
+        degrees: u32,
+        /// Pages to rotate (e.g. "2-5", "odd", "2-end"; default: all pages)
+        #[arg(short, long)]
+        pages: Option<String>,
+        /// Output PDF file, for a single input (default: input_rotated.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Reorder, reverse or delete pages of a PDF file
+    #[command(group(ArgGroup::new("mode").required(true)))]
+    Reorder {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// New page order; pages may repeat (e.g. "5,1-4,5", "10-1")
+        #[arg(long, group = "mode")]
+        order: Option<String>,
+        /// Reverse the page order
+        #[arg(long, group = "mode")]
+        reverse: bool,
+        /// Pages to remove (e.g. "2", "1,3-5", "end")
+        #[arg(long, group = "mode")]
+        delete: Option<String>,
+        /// Output PDF file, for a single input (default: input_reordered.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Split a PDF into several files
+    #[command(group(ArgGroup::new("mode").required(true)))]
+    Split {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Start a new file every N pages
+        #[arg(long, group = "mode")]
+        every: Option<u32>,
+        /// Start a new file at each of these pages (e.g. "5,12,30")
+        #[arg(long, group = "mode")]
+        at: Option<String>,
+        /// Maximum size of each file, in MB
+        #[arg(long, group = "mode")]
+        max_size: Option<f64>,
+        /// Output directory (default: current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// File name template: {name}, {num}, {start}, {end} (".pdf" is added)
+        #[arg(long, default_value = DEFAULT_SPLIT_TEMPLATE)]
+        name: String,
+    },
+    /// Merge several PDF files into one
+    Merge {
+        /// Input PDF files, optionally with a page range (e.g. "a.pdf", "b.pdf:1-3")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output PDF file (default: first-input_merged.pdf)
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
     /// Convert PDF pages to images
     Convert {
-        /// Input PDF file
-        input: PathBuf,
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
         /// Output image format: png, jpeg
         #[arg(short, long, default_value = "png")]
         format: String,
         /// Resolution in DPI
         #[arg(short, long, default_value = "300")]
         dpi: u32,
+        /// Pages to convert (e.g. "1", "2-5", "odd"; default: all pages)
+        #[arg(short, long)]
+        pages: Option<String>,
         /// Output directory (default: current directory)
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
     /// Extract text content from a PDF to a text file
     Text {
-        /// Input PDF file
-        input: PathBuf,
-        /// Output text file (default: input.txt)
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output text file, for a single input (default: input.txt)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
     },
     /// Compress a PDF file
     Compress {
-        /// Input PDF file
-        input: PathBuf,
-        /// Compression quality: screen, ebook, printer, prepress
-        #[arg(short, long, default_value = "ebook")]
-        quality: String,
-        /// Output PDF file (default: input_compressed.pdf)
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        #[command(flatten)]
+        settings: CompressFlags,
+        /// Pick the best quality that keeps each output under this size, in MB
+        /// (replaces the other compression settings)
+        #[arg(
+            long,
+            value_name = "MB",
+            conflicts_with_all = [
+                "quality", "color_dpi", "gray_dpi", "mono_dpi", "jpeg_quality",
+                "downsample_threshold", "embed_fonts", "subset_fonts", "keep_original", "linearize",
+            ]
+        )]
+        max_size: Option<f64>,
+        /// Output PDF file, for a single input (default: input_compressed.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Convert a PDF file to grayscale, black and white, or CMYK
+    Color {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Target colours: gray, mono (pure black and white), cmyk
+        #[arg(short, long, default_value = "gray")]
+        mode: String,
+        /// For mono: shades darker than this percentage become black (1-99)
+        #[arg(short, long, default_value_t = 50)]
+        threshold: u8,
+        /// Output PDF file, for a single input (default: input_gray.pdf, input_mono.pdf...)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Convert a PDF file to PDF/A for long-term archiving
+    Pdfa {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Conformance level: 1b, 2b, 3b
+        #[arg(short, long, default_value = "2b")]
+        level: String,
+        /// Output PDF file, for a single input (default: input_pdfa.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Linearize a PDF file ("fast web view") for streaming in browsers
+    Linearize {
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Only report whether each file is already linearized
+        #[arg(long)]
+        check: bool,
+        /// Output PDF file, for a single input (default: input_linearized.pdf)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Rewrite PDF files as another PDF version, e.g. 1.3 for old printers
+    SetVersion {
+        /// Target version: 1.3 to 1.7, or 2.0
+        version: String,
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output PDF file, for a single input (default: input_v1.4.pdf...)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
     },
     /// Convert a PDF file to Markdown
     PdfToMd {
-        /// Input PDF file
-        input: PathBuf,
-        /// Output Markdown file (default: input.md)
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output Markdown file, for a single input (default: input.md)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Run the steps of a TOML or YAML recipe on each input
+    Pipeline {
+        /// Recipe file (.toml, .yaml or .yml)
+        recipe: PathBuf,
+        /// Input PDF files or glob patterns (e.g. "scans/*.pdf")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output file, for a single input (default: input_pipeline.pdf); a directory
+        /// when the recipe ends with convert
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
+    },
+    /// Watch a folder and process every PDF written into it
+    Watch {
+        /// Folder to watch
+        dir: PathBuf,
+        /// Operation to run on each PDF: compress, text, pdf-to-md
+        #[arg(long)]
+        action: String,
+        /// Compression quality for --action compress: screen, ebook, printer, prepress
+        #[arg(short, long, default_value = "ebook")]
+        quality: String,
+        /// Directory for the results, not DIR itself (default: DIR/output)
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+        /// Output file name template: {name} (input file name), {num} (files processed so far);
+        /// the extension is added
+        #[arg(long, value_name = "TEMPLATE")]
+        name: Option<String>,
+        /// Seconds a file must stay unchanged before it is processed
+        #[arg(long, default_value_t = 2, value_name = "SECONDS")]
+        settle: u64,
     },
     /// Convert a Markdown file to PDF
     MdToPdf {
-        /// Input Markdown file
-        input: PathBuf,
-        /// Output PDF file (default: input.pdf)
+        /// Input Markdown files or glob patterns (e.g. "notes/*.md")
+        #[arg(required = true)]
+        inputs: Vec<String>,
+        /// Output PDF file, for a single input (default: input.pdf)
         #[arg(short, long)]
         output: Option<PathBuf>,
+        #[command(flatten)]
+        batch: BatchOutput,
     },
 }
 
+/// Exit status for each kind of error, so scripts can tell them apart.
+/// 2 matches clap's status for bad command-line arguments.
+fn exit_code(error: &PdfToolError) -> i32 {
+    match error {
+        PdfToolError::Io(_) => 1,
+        PdfToolError::InvalidArgument(_) => 2,
+        PdfToolError::InputNotFound(_) => 3,
+        PdfToolError::InvalidPdf(_) => 4,
+        PdfToolError::ToolNotFound { .. } => 5,
+        PdfToolError::ToolFailed { .. } => 6,
+        PdfToolError::Timeout { .. } => 7,
+        // Conventional status for a process stopped by Ctrl-C
+        PdfToolError::Cancelled => 130,
+    }
+}
+
+/// Print `error` and exit with its status code.
+fn fail(error: PdfToolError) -> ! {
+    PROGRESS.finish_and_clear();
+    eprintln!("Error: {}", error);
+    process::exit(exit_code(&error));
+}
+
+/// Progress bar shared by the operations. Drawn on stderr, only when it is a terminal.
+static PROGRESS: LazyLock<ProgressBar> = LazyLock::new(|| {
+    let bar = ProgressBar::new(0);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} pages ({eta})")
+            .expect("valid progress template"),
+    );
+    bar
+});
+
+fn show_progress(progress: Progress) {
+    PROGRESS.set_length(progress.total as u64);
+    PROGRESS.set_position(progress.done as u64);
+    // Clear the bar before the command prints its result.
+    if progress.done >= progress.total {
+        PROGRESS.finish_and_clear();
+    }
+}
+
+impl BatchOutput {
+    /// Output file for the `num`th input: `-o` for a single input, otherwise
+    /// `template` (or `--name`) in `--out-dir` or next to the input.
+    fn path(
+        &self,
+        output: &Option<PathBuf>,
+        input: &Path,
+        num: usize,
+        template: &str,
+        ext: &str,
+    ) -> PathBuf {
+        if let Some(output) = output {
+            return output.clone();
+        }
+        let name = render_output_name(self.name.as_deref().unwrap_or(template), input, num);
+        let dir = match &self.out_dir {
+            Some(dir) => dir.as_path(),
+            None => input.parent().unwrap_or_else(|| Path::new(".")),
+        };
+        dir.join(format!("{}.{}", name, ext))
+    }
+
+    /// Check the options against the inputs and create `--out-dir`. Several inputs
+    /// must not be written to the same file, as they may run at the same time, and
+    /// no output may overwrite an input.
+    fn prepare(
+        &self,
+        output: &Option<PathBuf>,
+        inputs: &[PathBuf],
+        template: &str,
+        ext: &str,
+    ) -> Result<(), PdfToolError> {
+        self.check(output, inputs, template, ext, false)
+    }
+
+    /// [`prepare`](Self::prepare) for commands that can rewrite a file in place:
+    /// an input may be its own output, but not another input's.
+    fn prepare_in_place(
+        &self,
+        output: &Option<PathBuf>,
+        inputs: &[PathBuf],
+        template: &str,
+        ext: &str,
+    ) -> Result<(), PdfToolError> {
+        self.check(output, inputs, template, ext, true)
+    }
+
+    fn check(
+        &self,
+        output: &Option<PathBuf>,
+        inputs: &[PathBuf],
+        template: &str,
+        ext: &str,
+        in_place: bool,
+    ) -> Result<(), PdfToolError> {
+        if output.is_some() && inputs.len() > 1 {
+            return Err(PdfToolError::InvalidArgument(
+                "-o/--output takes a single input; use --out-dir and --name with several"
+                    .to_string(),
+            ));
+        }
+        if let Some(name) = &self.name
+            && inputs.len() > 1
+            && !name.contains("{name}")
+            && !name.contains("{num}")
+        {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "--name '{}' gives every file the same name; add {{name}} or {{num}}",
+                name
+            )));
+        }
+        let read: HashMap<PathBuf, &Path> = inputs
+            .iter()
+            .map(|input| (canonical(input), input.as_path()))
+            .collect();
+        let mut written: HashMap<PathBuf, &Path> = HashMap::new();
+        for (i, input) in inputs.iter().enumerate() {
+            let path = self.path(output, input, i + 1, template, ext);
+            if let Some(&other) = read.get(&canonical(&path))
+                && !(in_place && other == input)
+            {
+                return Err(PdfToolError::InvalidArgument(format!(
+                    "{} would be written over the input {}; choose another --name, --out-dir or -o",
+                    path.display(),
+                    other.display()
+                )));
+            }
+            if let Some(other) = written.insert(canonical(&path), input) {
+                return Err(PdfToolError::InvalidArgument(format!(
+                    "{} and {} would both be written to {}; add {{num}} to --name",
+                    other.display(),
+                    input.display(),
+                    path.display()
+                )));
+            }
+        }
+        if let Some(dir) = &self.out_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// `path` with its directory resolved, so that different spellings of the same
+/// file compare equal. Left as given when the directory does not exist yet.
+fn canonical(path: &Path) -> PathBuf {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    match (dir.canonicalize(), path.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Expand the input patterns, or exit if one matches nothing.
+fn input_files(patterns: &[String]) -> Vec<PathBuf> {
+    match expand_inputs(patterns) {
+        Ok(files) => files,
+        Err(e) => fail(e),
+    }
+}
+
+/// Run `work` on every input and print the outcome. A single input behaves as a
+/// plain command: its message is printed or the program exits with its error.
+/// Several inputs are processed `jobs` at a time and followed by a per-file summary;
+/// if any failed, the program exits with the status of the first failure.
+fn run_inputs(
+    runner: &ToolRunner,
+    inputs: &[PathBuf],
+    jobs: usize,
+    work: impl Fn(&ToolRunner, &Path, usize) -> Result<String, PdfToolError> + Sync,
+) {
+    if let [input] = inputs {
+        match work(&runner.clone().on_progress(show_progress), input, 1) {
+            Ok(message) => println!("{}", message),
+            Err(e) => fail(e),
+        }
+        return;
+    }
+
+    // With several files the bar counts files; page progress would interleave.
+    PROGRESS.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} files ({eta})")
+            .expect("valid progress template"),
+    );
+    PROGRESS.set_length(inputs.len() as u64);
+    let results = run_batch(runner, inputs, jobs, |input, num| {
+        let result = work(runner, input, num);
+        PROGRESS.inc(1);
+        result
+    });
+    PROGRESS.finish_and_clear();
+
+    let mut failed = Vec::new();
+    for (input, result) in inputs.iter().zip(results) {
+        match result {
+            Ok(message) => println!("ok      {}: {}", input.display(), message),
+            Err(e) => {
+                println!("failed  {}: {}", input.display(), e);
+                failed.push(e);
+            }
+        }
+    }
+    println!(
+        "{} files: {} done, {} failed",
+        inputs.len(),
+        inputs.len() - failed.len(),
+        failed.len()
+    );
+
+    // Ctrl-C wins over the errors of the files that ran before it.
+    let first = failed
+        .iter()
+        .find(|e| matches!(e, PdfToolError::Cancelled))
+        .or(failed.first());
+    if let Some(error) = first {
+        process::exit(exit_code(error));
+    }
+}
+
+/// Print the result of `inspect_pdf` as a human-readable summary.
+fn print_info(info: &PdfInfo) {
+    println!("File:       {}", info.path.display());
+    println!("Size:       {}", format_size(info.file_size));
+    println!("Version:    PDF {}", info.version);
+    println!("Pages:      {}", info.page_count);
+    for (label, value) in [
+        ("Title", &info.title),
+        ("Author", &info.author),
+        ("Creator", &info.creator),
+        ("Producer", &info.producer),
+    ] {
+        if let Some(value) = value {
+            println!("{:<12}{}", format!("{}:", label), value);
+        }
+    }
+    println!("Encrypted:  {}", if info.encrypted { "yes" } else { "no" });
+    println!("Images:     {}", info.image_count);
+    println!("Linearized: {}", if info.linearized { "yes" } else { "no" });
+
+    println!();
+    println!("Page sizes:");
+    // Consecutive pages with the same size and rotation are printed as one line.
+    let mut start = 0;
+    while start < info.pages.len() {
+        let page = &info.pages[start];
+        let mut end = start;
+        while end + 1 < info.pages.len()
+            && info.pages[end + 1].width == page.width
+            && info.pages[end + 1].height == page.height
+            && info.pages[end + 1].rotation == page.rotation
+        {
+            end += 1;
+        }
+        let range = if start == end {
+            format!("{}", page.number)
+        } else {
+            format!("{}-{}", page.number, info.pages[end].number)
+        };
+        let rotation = if page.rotation != 0 {
+            format!(", rotated {}°", page.rotation)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<10}{:.0} x {:.0} pt ({:.0} x {:.0} mm){}",
+            range,
+            page.width,
+            page.height,
+            page.width * 25.4 / 72.0,
+            page.height * 25.4 / 72.0,
+            rotation
+        );
+        start = end + 1;
+    }
+
+    println!();
+    if info.fonts.is_empty() {
+        println!("Fonts: none");
+    } else {
+        println!("Fonts:");
+        for font in &info.fonts {
+            let embedded = if font.embedded {
+                "embedded"
+            } else {
+                "not embedded"
+            };
+            println!("  {} ({}, {})", font.name, font.kind, embedded);
+        }
+    }
+}
+
+fn print_environment(report: &EnvironmentReport) {
+    println!("Tools:");
+    for tool in &report.tools {
+        let state = match (&tool.version, &tool.error) {
+            (Some(version), _) => version.clone(),
+            (None, Some(error)) => format!("broken: {}", error.lines().next().unwrap_or_default()),
+            (None, None) => "not found".to_string(),
+        };
+        println!("  {:<13}{}", tool.name, state);
+        if let (Some(path), Some(source)) = (&tool.path, &tool.source) {
+            println!("  {:<13}{} ({})", "", path.display(), source);
+        }
+    }
+
+    println!();
+    let mut unavailable = report.unavailable().peekable();
+    if unavailable.peek().is_none() {
+        println!("All features are available.");
+    } else {
+        println!("Unavailable features:");
+        for feature in unavailable {
+            println!(
+                "  {} ({})",
+                feature.name,
+                feature.reason.as_deref().unwrap_or_default()
+            );
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!(
+            "{:.1} MB ({} bytes)",
+            bytes as f64 / (1024.0 * 1024.0),
+            bytes
+        )
+    } else if bytes >= 1024 {
+        format!("{:.1} KB ({} bytes)", bytes as f64 / 1024.0, bytes)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
+    // The first Ctrl-C stops the running tool; the operation then removes its
+    // half-written output and we exit through `fail`. A second one exits at once.
+    let cancel = CancelToken::new();
+    let handler_token = cancel.clone();
+    let _ = ctrlc::set_handler(move || {
+        if handler_token.is_cancelled() {
+            process::exit(130);
+        }
+        handler_token.cancel();
+    });
 
+    let mut runner = ToolRunner::default().with_cancel(cancel);
+    if let Some(seconds) = cli.timeout {
+        runner = runner.with_timeout(Duration::from_secs(seconds));
+    }
+    if cli.verbose {
+        runner = runner.on_launch(|located| PROGRESS.suspend(|| eprintln!("Using {}", located)));
+    }
+    if let Some(version) = &cli.pdf_version {
+        match PdfVersion::parse(version) {
+            Ok(version) => runner = runner.with_pdf_version(version),
+            Err(e) => fail(e),
+        }
+    }
+    runner = runner.on_warning(|warning| PROGRESS.suspend(|| eprintln!("Warning: {}", warning)));
+
+    let jobs = cli.jobs;
     match cli.command {
+        Commands::Info {
+            inputs: patterns,
+            json,
+        } => {
+            let inputs = input_files(&patterns);
+            let results = run_batch(&runner, &inputs, jobs, |input, _| inspect_pdf(input));
+
+            let mut infos = Vec::new();
+            let mut first_error = None;
+            for result in results {
+                match result {
+                    Ok(info) => infos.push(info),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+
+            if json {
+                // One input prints an object, several an array of them.
+                let text = match infos.as_slice() {
+                    [info] if inputs.len() == 1 => serde_json::to_string_pretty(info),
+                    _ => serde_json::to_string_pretty(&infos),
+                };
+                match text {
+                    Ok(text) => println!("{}", text),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                }
+            } else {
+                for (i, info) in infos.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    print_info(info);
+                }
+            }
+            if let Some(e) = first_error {
+                process::exit(exit_code(&e));
+            }
+        }
+
+        Commands::Doctor { json } => {
+            let report = check_environment(&runner);
+            if json {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(text) => println!("{}", text),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        process::exit(1);
+                    }
+                }
+            } else {
+                print_environment(&report);
+            }
+        }
+
         Commands::Extract {
-            input,
+            inputs: patterns,
             pages,
             output,
+            batch,
         } => {
-            let pages = match parse_page_range(&pages) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("Error parsing page range: {}", e);
-                    process::exit(1);
-                }
-            };
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_extracted", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let pages = resolve_page_range(&pages, page_count(input)?)?;
+                let output = batch.path(&output, input, num, "{name}_extracted", "pdf");
+                extract_pages(runner, input, &output, &pages)?;
+                Ok(format!("Extracted pages to {}", output.display()))
+            });
+        }
 
-            let output = output.unwrap_or_else(|| {
-                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-                let parent = input.parent().unwrap_or_else(|| std::path::Path::new("."));
-                parent.join(format!("{}_extracted.pdf", stem))
+        Commands::Rotate {
+            inputs: patterns,
+            degrees,
+            pages,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_rotated", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let pages = match &pages {
+                    Some(p) => Some(resolve_page_range(p, page_count(input)?)?),
+                    None => None,
+                };
+                let output = batch.path(&output, input, num, "{name}_rotated", "pdf");
+                rotate_pages(runner, input, &output, pages.as_deref(), degrees)?;
+                Ok(format!("Rotated PDF saved to {}", output.display()))
+            });
+        }
+
+        Commands::Reorder {
+            inputs: patterns,
+            order,
+            reverse,
+            delete,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_reordered", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let mode = if reverse {
+                    ReorderMode::Reverse
+                } else if let Some(order) = &order {
+                    ReorderMode::Sequence(resolve_page_sequence(order, page_count(input)?)?)
+                } else {
+                    let delete = delete.as_deref().unwrap_or_default();
+                    ReorderMode::Delete(resolve_page_range(delete, page_count(input)?)?)
+                };
+                let output = batch.path(&output, input, num, "{name}_reordered", "pdf");
+                reorder_pages(runner, input, &output, &mode)?;
+                Ok(format!("Reordered PDF saved to {}", output.display()))
             });
+        }
 
-            if let Err(e) = extract_pages(&input, &output, &pages) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+        Commands::Split {
+            inputs: patterns,
+            every,
+            at,
+            max_size,
+            output,
+            name,
+        } => {
+            if max_size.is_some_and(|mb| mb <= 0.0) {
+                eprintln!("Error: --max-size must be greater than 0");
+                process::exit(2);
             }
-            println!("Extracted pages to {}", output.display());
+            let inputs = input_files(&patterns);
+            let output_dir = output.unwrap_or_else(|| PathBuf::from("."));
+
+            run_inputs(&runner, &inputs, jobs, |runner, input, _| {
+                let mode = if let Some(n) = every {
+                    SplitMode::EveryN(n)
+                } else if let Some(at) = &at {
+                    SplitMode::AtPages(resolve_page_range(at, page_count(input)?)?)
+                } else {
+                    SplitMode::MaxBytes((max_size.unwrap_or_default() * 1024.0 * 1024.0) as u64)
+                };
+                let files = split_pdf(runner, input, &output_dir, &mode, &name)?;
+                Ok(format!(
+                    "Split PDF into {} files in {}",
+                    files.len(),
+                    output_dir.display()
+                ))
+            });
         }
 
-        Commands::Text { input, output } => {
+        Commands::Merge { inputs, output } => {
+            // Globs are expanded in place; "file:pages" specs are kept as written.
+            let mut merge_inputs = Vec::new();
+            for spec in &inputs {
+                for spec in input_files(std::slice::from_ref(spec)) {
+                    let spec = spec.to_string_lossy();
+                    match parse_merge_input(&spec) {
+                        Ok(input) => merge_inputs.push(input),
+                        Err(e) => {
+                            eprintln!("Error parsing page range in '{}': {}", spec, e);
+                            process::exit(exit_code(&e));
+                        }
+                    }
+                }
+            }
+
             let output = output.unwrap_or_else(|| {
-                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-                let parent = input.parent().unwrap_or_else(|| std::path::Path::new("."));
-                parent.join(format!("{}.txt", stem))
+                let first = &merge_inputs[0].path;
+                let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+                let parent = first.parent().unwrap_or_else(|| Path::new("."));
+                parent.join(format!("{}_merged.pdf", stem))
             });
 
-            if let Err(e) = extract_text(&input, &output) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+            if let Err(e) = merge_pdfs(&runner.on_progress(show_progress), &merge_inputs, &output) {
+                fail(e);
+            }
+            println!(
+                "Merged {} files into {}",
+                merge_inputs.len(),
+                output.display()
+            );
+        }
+
+        Commands::Text {
+            inputs: patterns,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}", "txt") {
+                fail(e);
             }
-            println!("Text extracted to {}", output.display());
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, "{name}", "txt");
+                extract_text(runner, input, &output)?;
+                Ok(format!("Text extracted to {}", output.display()))
+            });
         }
 
         Commands::Compress {
-            input,
-            quality,
+            inputs: patterns,
+            settings,
+            max_size,
             output,
+            batch,
         } => {
-            let output = output.unwrap_or_else(|| {
-                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-                let parent = input.parent().unwrap_or_else(|| std::path::Path::new("."));
-                parent.join(format!("{}_compressed.pdf", stem))
+            if max_size.is_some_and(|mb| mb <= 0.0) {
+                eprintln!("Error: --max-size must be greater than 0");
+                process::exit(2);
+            }
+            let options = match settings.options() {
+                Ok(options) => options,
+                Err(e) => fail(e),
+            };
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_compressed", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, "{name}_compressed", "pdf");
+                if let Some(mb) = max_size {
+                    let fit =
+                        compress_to_size(runner, input, &output, (mb * 1024.0 * 1024.0) as u64)?;
+                    return Ok(format!(
+                        "Compressed PDF saved to {} ({}, {})",
+                        output.display(),
+                        fit.options,
+                        format_size(fit.report.output_size)
+                    ));
+                }
+                let report = compress_pdf_with(runner, input, &output, &options)?;
+                Ok(format!(
+                    "Compressed PDF saved to {}: {}",
+                    output.display(),
+                    report
+                ))
+            });
+        }
+
+        Commands::Color {
+            inputs: patterns,
+            mode,
+            threshold,
+            output,
+            batch,
+        } => {
+            let mode = match ColorMode::parse(&mode, threshold) {
+                Ok(mode) => mode,
+                Err(e) => fail(e),
+            };
+            let inputs = input_files(&patterns);
+            let template = format!("{{name}}_{}", mode);
+            if let Err(e) = batch.prepare(&output, &inputs, &template, "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, &template, "pdf");
+                convert_color(runner, input, &output, &mode)?;
+                Ok(format!("Converted PDF to {} in {}", mode, output.display()))
+            });
+        }
+
+        Commands::Pdfa {
+            inputs: patterns,
+            level,
+            output,
+            batch,
+        } => {
+            let level = match PdfaLevel::parse(&level) {
+                Ok(level) => level,
+                Err(e) => fail(e),
+            };
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_pdfa", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, "{name}_pdfa", "pdf");
+                to_pdfa(runner, input, &output, level)?;
+                Ok(format!("{} saved to {}", level, output.display()))
+            });
+        }
+
+        Commands::Linearize {
+            inputs: patterns,
+            check,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if check {
+                let mut first_error = None;
+                for input in &inputs {
+                    match is_linearized(input) {
+                        Ok(linearized) => {
+                            let state = if linearized {
+                                "linearized"
+                            } else {
+                                "not linearized"
+                            };
+                            println!("{}: {}", input.display(), state);
+                        }
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            first_error.get_or_insert(e);
+                        }
+                    }
+                }
+                if let Some(e) = first_error {
+                    process::exit(exit_code(&e));
+                }
+                return;
+            }
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}_linearized", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let already = if is_linearized(input)? {
+                    " (the input was already linearized)"
+                } else {
+                    ""
+                };
+                let output = batch.path(&output, input, num, "{name}_linearized", "pdf");
+                linearize_pdf(runner, input, &output)?;
+                Ok(format!(
+                    "Linearized PDF saved to {}{}",
+                    output.display(),
+                    already
+                ))
             });
+        }
 
-            if let Err(e) = compress_pdf(&input, &output, &quality) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+        Commands::SetVersion {
+            version,
+            inputs: patterns,
+            output,
+            batch,
+        } => {
+            let version = match PdfVersion::parse(&version) {
+                Ok(version) => version,
+                Err(e) => fail(e),
+            };
+            let inputs = input_files(&patterns);
+            let template = format!("{{name}}_v{}", version);
+            if let Err(e) = batch.prepare_in_place(&output, &inputs, &template, "pdf") {
+                fail(e);
             }
-            println!("Compressed PDF saved to {}", output.display());
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, &template, "pdf");
+                for warning in set_pdf_version(runner, input, &output, version)? {
+                    PROGRESS.suspend(|| eprintln!("Warning: {}: {}", input.display(), warning));
+                }
+                Ok(format!("PDF {} saved to {}", version, output.display()))
+            });
         }
 
         Commands::Convert {
-            input,
+            inputs: patterns,
             format,
             dpi,
+            pages,
             output,
         } => {
+            let inputs = input_files(&patterns);
             let output_dir = output.unwrap_or_else(|| PathBuf::from("."));
 
-            if let Err(e) = convert_pdf(&input, &output_dir, &format, dpi) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
-            }
-            println!("Converted PDF to {} images in {}", format, output_dir.display());
+            run_inputs(&runner, &inputs, jobs, |runner, input, _| {
+                let pages = match &pages {
+                    Some(p) => Some(resolve_page_range(p, page_count(input)?)?),
+                    None => None,
+                };
+                convert_pdf(runner, input, &output_dir, &format, dpi, pages.as_deref())?;
+                Ok(format!(
+                    "Converted PDF to {} images in {}",
+                    format,
+                    output_dir.display()
+                ))
+            });
         }
 
-        Commands::PdfToMd { input, output } => {
-            let output = output.unwrap_or_else(|| {
-                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-                let parent = input.parent().unwrap_or_else(|| std::path::Path::new("."));
-                parent.join(format!("{}.md", stem))
+        Commands::PdfToMd {
+            inputs: patterns,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}", "md") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, "{name}", "md");
+                pdf_to_md(runner, input, &output)?;
+                Ok(format!("Converted PDF to Markdown: {}", output.display()))
             });
+        }
 
-            if let Err(e) = pdf_to_md(&input, &output) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+        Commands::Pipeline {
+            recipe,
+            inputs: patterns,
+            output,
+            batch,
+        } => {
+            let pipeline = match Pipeline::load(&recipe) {
+                Ok(pipeline) => pipeline,
+                Err(e) => fail(e),
+            };
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(
+                &output,
+                &inputs,
+                "{name}_pipeline",
+                pipeline.output_extension().unwrap_or("pdf"),
+            ) {
+                fail(e);
             }
-            println!("Converted PDF to Markdown: {}", output.display());
-        }
+            let steps: Vec<String> = pipeline.steps.iter().map(|step| step.to_string()).collect();
 
-        Commands::MdToPdf { input, output } => {
-            let output = output.unwrap_or_else(|| {
-                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-                let parent = input.parent().unwrap_or_else(|| std::path::Path::new("."));
-                parent.join(format!("{}.pdf", stem))
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = match pipeline.output_extension() {
+                    Some(ext) => batch.path(&output, input, num, "{name}_pipeline", ext),
+                    // Images go into a directory, named after the input by convert.
+                    None => output
+                        .clone()
+                        .or_else(|| batch.out_dir.clone())
+                        .unwrap_or_else(|| {
+                            input
+                                .parent()
+                                .unwrap_or_else(|| Path::new("."))
+                                .to_path_buf()
+                        }),
+                };
+                pipeline.run(runner, input, &output)?;
+                Ok(format!(
+                    "{}: saved to {}",
+                    steps.join(" → "),
+                    output.display()
+                ))
             });
+        }
+
+        Commands::Watch {
+            dir,
+            action,
+            quality,
+            out_dir,
+            name,
+            settle,
+        } => {
+            let (template, ext) = match action.as_str() {
+                "compress" => ("{name}_compressed", "pdf"),
+                "text" => ("{name}", "txt"),
+                "pdf-to-md" => ("{name}", "md"),
+                other => fail(PdfToolError::InvalidArgument(format!(
+                    "Unknown watch action '{}' (use compress, text or pdf-to-md)",
+                    other
+                ))),
+            };
+            let template = name.as_deref().unwrap_or(template);
+            let out_dir = out_dir.unwrap_or_else(|| dir.join("output"));
+            if let Err(e) = std::fs::create_dir_all(&out_dir) {
+                fail(e.into());
+            }
+            // Results written into the watched folder would be picked up and processed again.
+            if let (Ok(out), Ok(watched)) = (out_dir.canonicalize(), dir.canonicalize())
+                && out == watched
+            {
+                fail(PdfToolError::InvalidArgument(
+                    "--out-dir must not be the watched folder; use a subfolder such as DIR/output"
+                        .to_string(),
+                ));
+            }
+            let mut options = WatchOptions::new(&dir);
+            options.settle = Duration::from_secs(settle);
 
-            if let Err(e) = md_to_pdf(&input, &output) {
-                eprintln!("Error: {}", e);
-                process::exit(1);
+            println!("Watching {} (Ctrl-C to stop)", dir.display());
+            let mut count = 0;
+            let result = watch_folder(
+                &runner,
+                &dir,
+                &options,
+                |input| {
+                    count += 1;
+                    let output = out_dir.join(format!(
+                        "{}.{}",
+                        render_output_name(template, input, count),
+                        ext
+                    ));
+                    match action.as_str() {
+                        "compress" => {
+                            compress_pdf(&runner, input, &output, &quality)?;
+                        }
+                        "text" => extract_text(&runner, input, &output)?,
+                        _ => pdf_to_md(&runner, input, &output)?,
+                    }
+                    Ok(format!("saved {}", output.display()))
+                },
+                |line| println!("{}", line),
+            );
+            if let Err(e) = result {
+                fail(e);
             }
-            println!("Converted Markdown to PDF: {}", output.display());
+        }
+
+        Commands::MdToPdf {
+            inputs: patterns,
+            output,
+            batch,
+        } => {
+            let inputs = input_files(&patterns);
+            if let Err(e) = batch.prepare(&output, &inputs, "{name}", "pdf") {
+                fail(e);
+            }
+            run_inputs(&runner, &inputs, jobs, |runner, input, num| {
+                let output = batch.path(&output, input, num, "{name}", "pdf");
+                md_to_pdf(runner, input, &output)?;
+                Ok(format!("Converted Markdown to PDF: {}", output.display()))
+            });
         }
     }
 }
@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::{PdfToolError, ToolRunner};
+
+/// Turn command-line inputs into a list of files. Arguments containing `*`, `?`
+/// or `[` are expanded as glob patterns (sorted, so results are stable); other
+/// arguments are kept as they are, so a missing file is reported by the operation.
+/// A file given twice is only processed once.
+pub fn expand_inputs(patterns: &[String]) -> Result<Vec<PathBuf>, PdfToolError> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for pattern in patterns {
+        if !is_glob(pattern) {
+            if !files.iter().any(|f| f.as_os_str() == pattern.as_str()) {
+                files.push(PathBuf::from(pattern));
+            }
+            continue;
+        }
+
+        let paths = glob::glob(pattern).map_err(|e| {
+            PdfToolError::InvalidArgument(format!("Invalid pattern '{}': {}", pattern, e))
+        })?;
+        let mut matched: Vec<PathBuf> = paths.flatten().filter(|p| p.is_file()).collect();
+        if matched.is_empty() {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "No files match '{}'",
+                pattern
+            )));
+        }
+        matched.sort();
+        for path in matched {
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Fill in an output name template for one input of a batch: `{name}` is the input
+/// file stem and `{num}` its position in the batch, starting at 1.
+pub fn render_output_name(template: &str, input: &Path, num: usize) -> String {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    template
+        .replace("{name}", &stem)
+        .replace("{num}", &num.to_string())
+}
+
+/// Run `work` on every input, at most `jobs` at a time. `work` receives the input
+/// and its position (starting at 1). Results are returned in input order; a failed
+/// file does not stop the others, but once `runner` is cancelled the files not yet
+/// started fail with `PdfToolError::Cancelled`.
+pub fn run_batch<T: Send>(
+    runner: &ToolRunner,
+    inputs: &[PathBuf],
+    jobs: usize,
+    work: impl Fn(&Path, usize) -> Result<T, PdfToolError> + Sync,
+) -> Vec<Result<T, PdfToolError>> {
+    let results: Mutex<Vec<Option<Result<T, PdfToolError>>>> =
+        Mutex::new(inputs.iter().map(|_| None).collect());
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, inputs.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(input) = inputs.get(i) else {
+                        break;
+                    };
+                    let result = runner.check_cancelled().and_then(|()| work(input, i + 1));
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every input is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CancelToken;
+    use crate::test_pdf::temp_path;
+
+    #[test]
+    fn test_expand_inputs() {
+        let dir = temp_path("batch-glob");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.pdf", "a.pdf", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let pattern = dir.join("*.pdf").to_string_lossy().to_string();
+        let listed = dir.join("b.pdf").to_string_lossy().to_string();
+        let files = expand_inputs(&[listed, pattern, "missing.pdf".to_string()]).unwrap();
+        assert_eq!(
+            files,
+            [
+                dir.join("b.pdf"),
+                dir.join("a.pdf"),
+                PathBuf::from("missing.pdf")
+            ]
+        );
+
+        let none = dir.join("*.docx").to_string_lossy().to_string();
+        assert!(matches!(
+            expand_inputs(&[none]),
+            Err(PdfToolError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_output_name() {
+        let input = Path::new("scans/invoice.pdf");
+        assert_eq!(
+            render_output_name("{name}_compressed", input, 3),
+            "invoice_compressed"
+        );
+        assert_eq!(render_output_name("doc-{num}", input, 3), "doc-3");
+    }
+
+    #[test]
+    fn test_run_batch_keeps_order_and_failures() {
+        let inputs: Vec<PathBuf> = (1..=5)
+            .map(|i| PathBuf::from(format!("{}.pdf", i)))
+            .collect();
+        let results = run_batch(&ToolRunner::default(), &inputs, 3, |input, num| {
+            if num == 2 {
+                return Err(PdfToolError::InvalidArgument("bad".to_string()));
+            }
+            Ok(input.display().to_string())
+        });
+
+        assert_eq!(results.len(), 5);
+        assert!(results[1].is_err());
+        assert_eq!(results[4].as_ref().unwrap(), "5.pdf");
+    }
+
+    #[test]
+    fn test_run_batch_stops_when_cancelled() {
+        let cancel = CancelToken::new();
+        let runner = ToolRunner::default().with_cancel(cancel.clone());
+        let inputs: Vec<PathBuf> = (1..=3)
+            .map(|i| PathBuf::from(format!("{}.pdf", i)))
+            .collect();
+        let results = run_batch(&runner, &inputs, 1, |_, _| {
+            cancel.cancel();
+            Ok(())
+        });
+
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(PdfToolError::Cancelled)));
+        assert!(matches!(results[2], Err(PdfToolError::Cancelled)));
+    }
+}
@@ -0,0 +1,288 @@
+use std::fmt;
+use std::path::Path;
+
+use lopdf::{Dictionary, Document, Object};
+
+use crate::compress::run_gs_to_pdf;
+use crate::version::apply_pdf_version;
+use crate::{PdfToolError, ToolRunner, discard_on_error, page_count};
+
+/// Resolution pages are rendered at for `ColorMode::Mono`.
+const MONO_DPI: u32 = 300;
+
+/// Colour space `convert_color` converts a document to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// Shades of gray; text and vector graphics stay as they are
+    Gray,
+    /// Pure black and white: pixels darker than `threshold` percent become black,
+    /// the rest white. Pages are rendered to images, so text is no longer selectable.
+    Mono { threshold: u8 },
+    /// CMYK, for print shops that require it
+    Cmyk,
+}
+
+impl ColorMode {
+    /// Parse "gray", "mono" or "cmyk"; `threshold` is only used for mono.
+    pub fn parse(mode: &str, threshold: u8) -> Result<Self, PdfToolError> {
+        match mode {
+            "gray" | "grey" => Ok(ColorMode::Gray),
+            "mono" => Ok(ColorMode::Mono { threshold }),
+            "cmyk" => Ok(ColorMode::Cmyk),
+            _ => Err(PdfToolError::InvalidArgument(format!(
+                "Unknown colour mode '{}'. Must be one of: gray, mono, cmyk",
+                mode
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorMode::Gray => write!(f, "gray"),
+            ColorMode::Mono { .. } => write!(f, "mono"),
+            ColorMode::Cmyk => write!(f, "cmyk"),
+        }
+    }
+}
+
+/// Convert every page of a PDF to grayscale, black and white, or CMYK.
+///
+/// Output follows the runner's PDF version. Black and white output comes from
+/// Ghostscript's image device, which has no version setting, so it is rewritten
+/// at that version once its images are packed.
+pub fn convert_color(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    mode: &ColorMode,
+) -> Result<(), PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    let (mut args, setup) = match *mode {
+        ColorMode::Gray => (pdfwrite_args(runner, "Gray", "DeviceGray"), None),
+        ColorMode::Cmyk => (pdfwrite_args(runner, "CMYK", "DeviceCMYK"), None),
+        ColorMode::Mono { threshold } => {
+            if !(1..=99).contains(&threshold) {
+                return Err(PdfToolError::InvalidArgument(
+                    "Invalid threshold. Must be between 1 and 99".to_string(),
+                ));
+            }
+            // pdfwrite cannot make vector content 1-bit, so pages are rendered as
+            // gray images and a transfer function snaps every shade to black or white;
+            // the images are then repacked at 1 bit per pixel.
+            let args = vec!["-sDEVICE=pdfimage8".to_string(), format!("-r{}", MONO_DPI)];
+            let level = f32::from(threshold) / 100.0;
+            let setup = format!("{{ {:.2} lt {{ 0 }} {{ 1 }} ifelse }} settransfer", level);
+            (args, Some(setup))
+        }
+    };
+    args.extend(["-dNOPAUSE", "-dBATCH", "-dSAFER"].map(String::from));
+    runner.check_version_loss(input);
+
+    let total = page_count(input).unwrap_or(0);
+    run_gs_to_pdf(runner, input, output, args, setup, total)?;
+    if matches!(mode, ColorMode::Mono { .. }) {
+        discard_on_error(pack_mono_images(output), output)?;
+        // Losses were reported for the input above.
+        apply_pdf_version(&runner.without_warnings(), output)?;
+    }
+    Ok(())
+}
+
+/// Rewrite the 8-bit gray images of `path` as 1-bit images. Pixels are already
+/// black or white, so each one becomes a single bit (white from 128 up).
+fn pack_mono_images(path: &Path) -> Result<(), PdfToolError> {
+    let mut doc = Document::load(path)?;
+    for object in doc.objects.values_mut() {
+        let Object::Stream(stream) = object else {
+            continue;
+        };
+        let Some((width, height)) = gray8_image_size(&stream.dict) else {
+            continue;
+        };
+        let Ok(pixels) = stream.get_plain_content() else {
+            continue;
+        };
+        if pixels.len() != width * height {
+            continue;
+        }
+
+        let row_bytes = width.div_ceil(8);
+        let mut packed = vec![0u8; row_bytes * height];
+        for (y, row) in pixels.chunks(width).enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|&(_, &pixel)| pixel >= 128) {
+                packed[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        stream.dict.set("BitsPerComponent", 1);
+        stream.set_plain_content(packed);
+        stream.compress()?;
+    }
+    doc.save(path)?;
+    Ok(())
+}
+
+/// Width and height of an 8-bit DeviceGray image with the default decoding.
+fn gray8_image_size(dict: &Dictionary) -> Option<(usize, usize)> {
+    let is = |key: &[u8], value: &[u8]| {
+        dict.get(key)
+            .and_then(Object::as_name)
+            .is_ok_and(|name| name == value)
+    };
+    let number = |key: &[u8]| dict.get(key).and_then(Object::as_i64).ok();
+    if !is(b"Subtype", b"Image")
+        || !is(b"ColorSpace", b"DeviceGray")
+        || number(b"BitsPerComponent") != Some(8)
+    {
+        return None;
+    }
+    if dict.has(b"Decode") || dict.has(b"SMask") {
+        return None;
+    }
+    let width = usize::try_from(number(b"Width")?).ok()?;
+    let height = usize::try_from(number(b"Height")?).ok()?;
+    Some((width, height))
+}
+
+fn pdfwrite_args(runner: &ToolRunner, strategy: &str, model: &str) -> Vec<String> {
+    let mut args = runner.pdfwrite_args();
+    args.extend([
+        format!("-sColorConversionStrategy={}", strategy),
+        format!("-dProcessColorModel=/{}", model),
+        // Keep the original image compression instead of re-encoding as JPEG.
+        "-dAutoFilterColorImages=false".to_string(),
+        "-dAutoFilterGrayImages=false".to_string(),
+        "-dColorImageFilter=/FlateEncode".to_string(),
+        "-dGrayImageFilter=/FlateEncode".to_string(),
+    ]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_color_modes() {
+        let input = temp_path("color_in.pdf");
+        sample_pdf(&input, 2);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone());
+
+        convert_color(
+            &runner,
+            &input,
+            &temp_path("color_gray.pdf"),
+            &ColorMode::Gray,
+        )
+        .unwrap();
+        let mono = ColorMode::parse("mono", 40).unwrap();
+        convert_color(&runner, &input, &temp_path("color_mono.pdf"), &mono).unwrap();
+
+        let calls = fake.calls();
+        assert!(
+            calls[0]
+                .1
+                .contains(&"-sColorConversionStrategy=Gray".to_string())
+        );
+        assert!(calls[1].1.contains(&"-sDEVICE=pdfimage8".to_string()));
+        assert!(
+            calls[1]
+                .1
+                .contains(&"{ 0.40 lt { 0 } { 1 } ifelse } settransfer".to_string())
+        );
+        assert!(calls[1].1.last().unwrap().ends_with("color_in.pdf"));
+    }
+
+    #[test]
+    fn test_mono_writes_1_bit_images() {
+        // A 10x2 gray page image as pdfimage8 writes it: white, then black from the 3rd pixel.
+        let input = temp_path("color_mono_bits_in.pdf");
+        sample_pdf(&input, 1);
+        let mut doc = Document::load(&input).unwrap();
+        let row: Vec<u8> = (0..10).map(|x| if x < 2 { 255 } else { 0 }).collect();
+        let mut image = lopdf::Stream::new(
+            dictionary! {
+                "Type" => "XObject", "Subtype" => "Image", "Width" => 10, "Height" => 2,
+                "ColorSpace" => "DeviceGray", "BitsPerComponent" => 8,
+            },
+            [row.clone(), row].concat(),
+        );
+        image.compress().unwrap();
+        let image_id = doc.add_object(image);
+        doc.save(&input).unwrap();
+
+        let output = temp_path("color_mono_bits_out.pdf");
+        let mono = ColorMode::Mono { threshold: 50 };
+        convert_color(
+            &ToolRunner::new(FakeBackend::succeeding()),
+            &input,
+            &output,
+            &mono,
+        )
+        .unwrap();
+
+        let doc = Document::load(&output).unwrap();
+        let image = doc.get_object(image_id).unwrap().as_stream().unwrap();
+        assert_eq!(
+            image
+                .dict
+                .get(b"BitsPerComponent")
+                .unwrap()
+                .as_i64()
+                .unwrap(),
+            1
+        );
+        // Two bytes per row, padded with zeros
+        assert_eq!(
+            image.get_plain_content().unwrap(),
+            [0b1100_0000, 0, 0b1100_0000, 0]
+        );
+    }
+
+    #[test]
+    fn test_mono_at_runner_pdf_version() {
+        let input = temp_path("color_mono_version_in.pdf");
+        let output = temp_path("color_mono_version_out.pdf");
+        sample_pdf(&input, 1);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone())
+            .with_pdf_version(crate::PdfVersion::parse("1.3").unwrap());
+
+        convert_color(&runner, &input, &output, &ColorMode::Mono { threshold: 50 }).unwrap();
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[1].1.contains(&"-dCompatibilityLevel=1.3".to_string()));
+        assert!(
+            calls[1]
+                .1
+                .last()
+                .unwrap()
+                .ends_with("color_mono_version_out.pdf")
+        );
+    }
+
+    #[test]
+    fn test_invalid_color_settings() {
+        assert!(ColorMode::parse("sepia", 50).is_err());
+        let input = temp_path("color_threshold_in.pdf");
+        sample_pdf(&input, 1);
+        let fake = FakeBackend::succeeding();
+        let mono = ColorMode::Mono { threshold: 100 };
+        let result = convert_color(
+            &ToolRunner::new(fake.clone()),
+            &input,
+            &temp_path("color_out.pdf"),
+            &mono,
+        );
+        assert!(matches!(result, Err(PdfToolError::InvalidArgument(_))));
+        assert!(fake.calls().is_empty());
+    }
+}
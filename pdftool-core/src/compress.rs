@@ -1,60 +1,707 @@
+use std::fmt;
 use std::path::Path;
-use std::process::Command;
 
-use crate::{gs_command, gs_lib_path};
+use serde::{Deserialize, Serialize};
+
+use lopdf::Document;
+
+use crate::{PdfToolError, PdfVersion, ToolRunner, discard_on_error, inspect};
 
 const VALID_QUALITIES: &[&str] = &["screen", "ebook", "printer", "prepress"];
 
+/// Ghostscript settings for `compress_pdf_with`. Start from a preset and override
+/// what you need; `None` keeps the preset's value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressOptions {
+    /// Ghostscript PDFSETTINGS preset: screen, ebook, printer, prepress
+    pub preset: String,
+    /// Resolution colour images are downsampled to, in DPI
+    pub color_dpi: Option<u32>,
+    /// Resolution grayscale images are downsampled to, in DPI
+    pub gray_dpi: Option<u32>,
+    /// Resolution black-and-white images are downsampled to, in DPI
+    pub mono_dpi: Option<u32>,
+    /// JPEG quality (1-100) for colour and grayscale images, which are then always JPEG-encoded
+    pub jpeg_quality: Option<u8>,
+    /// Only downsample images whose resolution is at least this many times the target
+    /// (e.g. 1.5); Ghostscript's default is 1.5
+    pub downsample_threshold: Option<f32>,
+    /// Embed every font, including the standard 14
+    pub embed_fonts: Option<bool>,
+    /// Embed only the glyphs that are used
+    pub subset_fonts: Option<bool>,
+    /// PDF version of the output; `None` uses the runner's version
+    /// (see `ToolRunner::with_pdf_version`), or else the preset's
+    pub pdf_version: Option<PdfVersion>,
+    /// When the compressed file is not smaller than the input, write a copy of
+    /// the input instead
+    pub keep_original: bool,
+    /// Write a linearized ("fast web view") file
+    pub linearize: bool,
+}
+
+impl Default for CompressOptions {
+    fn default() -> Self {
+        CompressOptions {
+            preset: "ebook".to_string(),
+            color_dpi: None,
+            gray_dpi: None,
+            mono_dpi: None,
+            jpeg_quality: None,
+            downsample_threshold: None,
+            embed_fonts: None,
+            subset_fonts: None,
+            pdf_version: None,
+            keep_original: false,
+            linearize: false,
+        }
+    }
+}
+
+impl CompressOptions {
+    /// The settings of a quality preset, with nothing overridden.
+    pub fn from_preset(quality: &str) -> Result<Self, PdfToolError> {
+        let options = CompressOptions {
+            preset: quality.to_string(),
+            ..Default::default()
+        };
+        options.validate()?;
+        Ok(options)
+    }
+
+    fn validate(&self) -> Result<(), PdfToolError> {
+        let invalid = |msg: String| Err(PdfToolError::InvalidArgument(msg));
+        if !VALID_QUALITIES.contains(&self.preset.as_str()) {
+            return invalid(format!(
+                "Invalid quality '{}'. Must be one of: {}",
+                self.preset,
+                VALID_QUALITIES.join(", ")
+            ));
+        }
+        for (name, dpi) in [
+            ("colour", self.color_dpi),
+            ("gray", self.gray_dpi),
+            ("mono", self.mono_dpi),
+        ] {
+            if dpi.is_some_and(|dpi| !(1..=2400).contains(&dpi)) {
+                return invalid(format!(
+                    "Invalid {} image DPI. Must be between 1 and 2400",
+                    name
+                ));
+            }
+        }
+        if self.jpeg_quality.is_some_and(|q| !(1..=100).contains(&q)) {
+            return invalid("Invalid JPEG quality. Must be between 1 and 100".to_string());
+        }
+        if self
+            .downsample_threshold
+            .is_some_and(|t| t.is_nan() || t < 1.0)
+        {
+            return invalid("Invalid downsample threshold. Must be 1.0 or more".to_string());
+        }
+        Ok(())
+    }
+
+    /// Ghostscript arguments for these settings, after the device and PDF
+    /// version and before the output and input files.
+    fn gs_args(&self) -> Vec<String> {
+        let mut args = vec![format!("-dPDFSETTINGS=/{}", self.preset)];
+        for (kind, dpi) in [
+            ("Color", self.color_dpi),
+            ("Gray", self.gray_dpi),
+            ("Mono", self.mono_dpi),
+        ] {
+            if let Some(dpi) = dpi {
+                args.push(format!("-dDownsample{}Images=true", kind));
+                args.push(format!("-d{}ImageResolution={}", kind, dpi));
+            }
+            if let Some(threshold) = self.downsample_threshold {
+                args.push(format!("-d{}ImageDownsampleThreshold={}", kind, threshold));
+            }
+        }
+        if self.jpeg_quality.is_some() {
+            // Without this Ghostscript may pick lossless compression for some images.
+            for kind in ["Color", "Gray"] {
+                args.push(format!("-dAutoFilter{}Images=false", kind));
+                args.push(format!("-d{}ImageFilter=/DCTEncode", kind));
+            }
+        }
+        if let Some(embed) = self.embed_fonts {
+            args.push(format!("-dEmbedAllFonts={}", embed));
+        }
+        if let Some(subset) = self.subset_fonts {
+            args.push(format!("-dSubsetFonts={}", subset));
+        }
+        if self.linearize {
+            args.push("-dFastWebView=true".to_string());
+        }
+        args.extend(["-dNOPAUSE", "-dBATCH", "-dSAFER"].map(String::from));
+        args
+    }
+
+    /// PostScript run before the input to set the JPEG quality, which has no
+    /// command-line switch in pdfwrite.
+    fn jpeg_setup(&self) -> Option<String> {
+        let quality = f32::from(self.jpeg_quality?);
+        // IJG quality to Ghostscript's QFactor: 50 → 0.5 (the default), 100 → ~0
+        let qfactor = if quality < 50.0 {
+            25.0 / quality
+        } else {
+            (100.0 - quality) / 100.0
+        }
+        .max(0.01);
+        let dict = format!(
+            "<< /QFactor {:.2} /Blend 1 /HSamples [1 1 1 1] /VSamples [1 1 1 1] >>",
+            qfactor
+        );
+        Some(format!(
+            "<< /ColorImageDict {} /GrayImageDict {} >> setdistillerparams",
+            dict, dict
+        ))
+    }
+}
+
+impl fmt::Display for CompressOptions {
+    /// The preset followed by the settings that override it, e.g.
+    /// "ebook, colour 120 DPI, gray 120 DPI, JPEG quality 75".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.preset)?;
+        for (name, dpi) in [
+            ("colour", self.color_dpi),
+            ("gray", self.gray_dpi),
+            ("mono", self.mono_dpi),
+        ] {
+            if let Some(dpi) = dpi {
+                write!(f, ", {} {} DPI", name, dpi)?;
+            }
+        }
+        if let Some(quality) = self.jpeg_quality {
+            write!(f, ", JPEG quality {}", quality)?;
+        }
+        if let Some(threshold) = self.downsample_threshold {
+            write!(f, ", downsample threshold {}", threshold)?;
+        }
+        if let Some(embed) = self.embed_fonts {
+            write!(
+                f,
+                ", {}",
+                if embed {
+                    "all fonts embedded"
+                } else {
+                    "fonts not embedded"
+                }
+            )?;
+        }
+        if let Some(subset) = self.subset_fonts {
+            write!(
+                f,
+                ", {}",
+                if subset { "fonts subset" } else { "full fonts" }
+            )?;
+        }
+        if let Some(version) = self.pdf_version {
+            write!(f, ", PDF {}", version)?;
+        }
+        if self.linearize {
+            write!(f, ", linearized")?;
+        }
+        if self.keep_original {
+            write!(f, ", original kept if not smaller")?;
+        }
+        Ok(())
+    }
+}
+
 pub fn compress_pdf(
+    runner: &ToolRunner,
     input: &Path,
     output: &Path,
     quality: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<CompressReport, PdfToolError> {
+    compress_pdf_with(
+        runner,
+        input,
+        output,
+        &CompressOptions::from_preset(quality)?,
+    )
+}
+
+/// Compress with detailed settings; see `CompressOptions`.
+pub fn compress_pdf_with(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    options: &CompressOptions,
+) -> Result<CompressReport, PdfToolError> {
     if !input.exists() {
-        return Err(format!("Input file not found: {}", input.display()).into());
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
     }
+    options.validate()?;
 
-    if !VALID_QUALITIES.contains(&quality) {
-        return Err(format!(
-            "Invalid quality '{}'. Must be one of: {}",
-            quality,
-            VALID_QUALITIES.join(", ")
-        )
-        .into());
+    // The page count and images are only reported, so a file lopdf cannot read
+    // is still handed to Ghostscript.
+    let before = Contents::of(input);
+    write_compressed(runner, input, output, options, before.pages)?;
+    compress_report(input, output, options.keep_original, before)
+}
+
+/// Run Ghostscript for `compress_pdf_with`, without building a report.
+fn write_compressed(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    options: &CompressOptions,
+    pages: u32,
+) -> Result<(), PdfToolError> {
+    let runner = match options.pdf_version {
+        Some(version) => runner.clone().with_pdf_version(version),
+        None => runner.clone(),
+    };
+    runner.check_version_loss(input);
+
+    let mut args = runner.pdfwrite_args();
+    args.extend(options.gs_args());
+    run_gs_to_pdf(&runner, input, output, args, options.jpeg_setup(), pages)
+}
+
+/// Compare `output` with `input`, first replacing it by a copy of the input when
+/// `keep_original` is set and it is not smaller. `before` describes the input.
+fn compress_report(
+    input: &Path,
+    output: &Path,
+    keep_original: bool,
+    before: Contents,
+) -> Result<CompressReport, PdfToolError> {
+    let input_size = std::fs::metadata(input)?.len();
+    let mut output_size = std::fs::metadata(output)?.len();
+    let kept_original = keep_original && output_size >= input_size;
+    let after = if kept_original {
+        std::fs::copy(input, output)?;
+        output_size = input_size;
+        before
+    } else {
+        Contents::of(output)
+    };
+
+    Ok(CompressReport {
+        input_size,
+        output_size,
+        ratio: if input_size == 0 {
+            1.0
+        } else {
+            output_size as f64 / input_size as f64
+        },
+        pages: after.pages,
+        images_before: before.images,
+        images_after: after.images,
+        kept_original,
+    })
+}
+
+/// Sizes before and after compression, returned by `compress_pdf_with`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompressReport {
+    /// Size of the input, in bytes
+    pub input_size: u64,
+    /// Size of the output, in bytes
+    pub output_size: u64,
+    /// `output_size / input_size`: below 1.0 when the file got smaller
+    pub ratio: f64,
+    pub pages: u32,
+    pub images_before: ImageStats,
+    pub images_after: ImageStats,
+    /// The compressed file was not smaller, so the output is a copy of the input
+    pub kept_original: bool,
+}
+
+/// Image XObjects of a document. Zero when the file could not be parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ImageStats {
+    pub count: usize,
+    /// Size of the image streams as stored, in bytes
+    pub bytes: u64,
+}
+
+impl fmt::Display for CompressReport {
+    /// A one-line summary, e.g.
+    /// "2.4 MB → 812.0 KB (-67%), 12 pages, 8 images: 2.1 MB → 650.3 KB".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.kept_original {
+            write!(
+                f,
+                "not smaller, original kept ({})",
+                short_size(self.input_size)
+            )?;
+        } else {
+            let change = (self.ratio - 1.0) * 100.0;
+            write!(
+                f,
+                "{} → {} ({:+.0}%)",
+                short_size(self.input_size),
+                short_size(self.output_size),
+                change
+            )?;
+        }
+        write!(f, ", {} pages", self.pages)?;
+        let (before, after) = (self.images_before, self.images_after);
+        if before.count > 0 || after.count > 0 {
+            write!(
+                f,
+                ", {} images: {} → {}",
+                before.count,
+                short_size(before.bytes),
+                short_size(after.bytes)
+            )?;
+        }
+        Ok(())
     }
+}
 
-    let mut cmd = Command::new(gs_command());
+fn short_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} bytes", bytes)
+    }
+}
 
-    if let Some(gs_dir) = gs_lib_path() {
-        let search_path = format!(
-            "{};{};{}",
-            gs_dir.join("lib").display(),
-            gs_dir.join("Resource").display(),
-            gs_dir.join("iccprofiles").display(),
+#[derive(Clone, Copy)]
+struct Contents {
+    pages: u32,
+    images: ImageStats,
+}
+
+impl Contents {
+    fn of(path: &Path) -> Self {
+        let Ok(doc) = Document::load(path) else {
+            return Contents {
+                pages: 0,
+                images: ImageStats::default(),
+            };
+        };
+        let mut images = ImageStats::default();
+        for stream in inspect::images(&doc) {
+            images.count += 1;
+            images.bytes += stream.content.len() as u64;
+        }
+        Contents {
+            pages: doc.get_pages().len() as u32,
+            images,
+        }
+    }
+}
+
+/// Run Ghostscript with `args` (device and settings) to write `input` to `output`,
+/// removing a partial output on failure. `setup` is PostScript run before the
+/// input is read. `pages` only drives progress.
+pub(crate) fn run_gs_to_pdf(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    mut args: Vec<String>,
+    setup: Option<String>,
+    pages: u32,
+) -> Result<(), PdfToolError> {
+    args.push(format!("-sOutputFile={}", output.display()));
+    if let Some(setup) = setup {
+        args.extend(["-c".to_string(), setup, "-f".to_string()]);
+    }
+    args.push(input.display().to_string());
+
+    let result = runner.run_gs_pages(&args, pages);
+    discard_on_error(result, output)?;
+    Ok(())
+}
+
+/// What `compress_to_size` settled on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SizeFit {
+    /// Settings that produced the output
+    pub options: CompressOptions,
+    /// Sizes and contents of the output kept
+    pub report: CompressReport,
+}
+
+/// Settings tried by `compress_to_size`, from the best quality to the smallest file.
+fn size_ladder() -> Vec<CompressOptions> {
+    let preset = |preset: &str| CompressOptions {
+        preset: preset.to_string(),
+        ..Default::default()
+    };
+    let images = |name: &str, dpi: u32, jpeg_quality: u8| CompressOptions {
+        color_dpi: Some(dpi),
+        gray_dpi: Some(dpi),
+        jpeg_quality: Some(jpeg_quality),
+        ..preset(name)
+    };
+    vec![
+        preset("prepress"),
+        preset("printer"),
+        images("printer", 200, 85),
+        preset("ebook"),
+        images("ebook", 120, 75),
+        preset("screen"),
+        images("screen", 72, 50),
+        CompressOptions {
+            mono_dpi: Some(150),
+            ..images("screen", 50, 30)
+        },
+    ]
+}
+
+/// Compress `input` so that `output` is at most `max_bytes`, keeping as much
+/// quality as possible. Settings are tried from the best quality down and the
+/// first result that fits is kept.
+///
+/// Fails with `PdfToolError::InvalidArgument` when even the smallest setting
+/// gives a larger file; `output` is then not written.
+pub fn compress_to_size(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    max_bytes: u64,
+) -> Result<SizeFit, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+    if max_bytes == 0 {
+        return Err(PdfToolError::InvalidArgument(
+            "Maximum size must be greater than 0".to_string(),
+        ));
+    }
+
+    let ladder = size_ladder();
+    let steps = ladder.len() as u32;
+    let attempt = output.with_extension("size-tmp.pdf");
+    let mut smallest: Option<(CompressOptions, u64)> = None;
+    runner.report_progress(0, steps);
+    runner.check_version_loss(input);
+    let attempt_runner = runner.without_progress().without_warnings();
+    // Read once; only the result that is kept is described in a report.
+    let before = Contents::of(input);
+
+    // Progress counts settings tried, not pages.
+    for (i, options) in ladder.into_iter().enumerate() {
+        write_compressed(&attempt_runner, input, &attempt, &options, before.pages)?;
+        let size = std::fs::metadata(&attempt)?.len();
+        runner.report_progress(i as u32 + 1, steps);
+
+        if size <= max_bytes {
+            if let Err(e) = std::fs::rename(&attempt, output) {
+                let _ = std::fs::remove_file(&attempt);
+                return Err(e.into());
+            }
+            runner.report_progress(steps, steps);
+            let report = compress_report(input, output, false, before)?;
+            return Ok(SizeFit { options, report });
+        }
+        if smallest
+            .as_ref()
+            .is_none_or(|(_, smallest)| size < *smallest)
+        {
+            smallest = Some((options, size));
+        }
+    }
+    let _ = std::fs::remove_file(&attempt);
+
+    let (options, size) = smallest.expect("the ladder is not empty");
+    Err(PdfToolError::InvalidArgument(format!(
+        "Cannot compress {} to {} bytes or less: the smallest result is {} bytes ({})",
+        input.display(),
+        max_bytes,
+        size,
+        options
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolOutput;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn test_quality_setting_is_passed() {
+        let input = temp_path("compress_args_in.pdf");
+        sample_pdf(&input, 1);
+        let fake = FakeBackend::succeeding();
+        compress_pdf(
+            &ToolRunner::new(fake.clone()),
+            &input,
+            &temp_path("compress_args_out.pdf"),
+            "ebook",
+        )
+        .unwrap();
+        assert!(
+            fake.calls()[0]
+                .1
+                .contains(&"-dPDFSETTINGS=/ebook".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unknown_quality_error() {
+        let input = temp_path("compress_quality_in.pdf");
+        sample_pdf(&input, 1);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone());
+        assert!(
+            compress_pdf(
+                &runner,
+                &input,
+                &temp_path("compress_quality_out.pdf"),
+                "tiny"
+            )
+            .is_err()
         );
-        cmd.env("GS_LIB", &search_path);
-    }
-
-    let status = cmd
-        .args([
-            "-sDEVICE=pdfwrite",
-            "-dCompatibilityLevel=1.4",
-            &format!("-dPDFSETTINGS=/{}", quality),
-            "-dNOPAUSE",
-            "-dBATCH",
-            "-dSAFER",
-            &format!("-sOutputFile={}", output.display()),
-            &input.display().to_string(),
-        ])
-        .status()?;
-
-    if !status.success() {
-        return Err(format!(
-            "Ghostscript exited with code: {}",
-            status.code().unwrap_or(-1)
+        assert!(fake.calls().is_empty());
+    }
+
+    #[test]
+    fn test_options_override_preset() {
+        let input = temp_path("compress_options_in.pdf");
+        sample_pdf(&input, 1);
+        let fake = FakeBackend::succeeding();
+        let options = CompressOptions {
+            color_dpi: Some(120),
+            jpeg_quality: Some(75),
+            subset_fonts: Some(false),
+            pdf_version: Some(PdfVersion::parse("1.7").unwrap()),
+            ..CompressOptions::from_preset("printer").unwrap()
+        };
+        compress_pdf_with(
+            &ToolRunner::new(fake.clone()),
+            &input,
+            &temp_path("compress_options_out.pdf"),
+            &options,
         )
-        .into());
+        .unwrap();
+
+        let args = &fake.calls()[0].1;
+        for expected in [
+            "-dPDFSETTINGS=/printer",
+            "-dCompatibilityLevel=1.7",
+            "-dColorImageResolution=120",
+            "-dColorImageFilter=/DCTEncode",
+            "-dSubsetFonts=false",
+        ] {
+            assert!(args.contains(&expected.to_string()), "missing {}", expected);
+        }
+        assert!(!args.iter().any(|a| a.starts_with("-dGrayImageResolution")));
+        // The JPEG settings run as PostScript just before the input file.
+        let c = args.iter().position(|a| a == "-c").unwrap();
+        assert!(args[c + 1].contains("/QFactor 0.25"));
+        assert_eq!(args[c + 2], "-f");
+        assert!(args.last().unwrap().ends_with("compress_options_in.pdf"));
     }
 
-    Ok(())
+    #[test]
+    fn test_invalid_options() {
+        let options = |change: fn(&mut CompressOptions)| {
+            let mut options = CompressOptions::default();
+            change(&mut options);
+            options.validate()
+        };
+        assert!(options(|_| {}).is_ok());
+        assert!(options(|o| o.jpeg_quality = Some(0)).is_err());
+        assert!(options(|o| o.downsample_threshold = Some(0.5)).is_err());
+        assert!(options(|o| o.mono_dpi = Some(0)).is_err());
+        // Versions are checked when options are read, e.g. from the GUI
+        assert!(toml::from_str::<CompressOptions>("pdf_version = \"1.8\"").is_err());
+        let read: CompressOptions = toml::from_str("pdf_version = \"1.4\"").unwrap();
+        assert_eq!(read.pdf_version, PdfVersion::parse("1.4").ok());
+    }
+
+    #[test]
+    fn test_report_and_keep_original() {
+        let input = temp_path("compress_report_in.pdf");
+        let output = temp_path("compress_report_out.pdf");
+        sample_pdf(&input, 3);
+        // A fake Ghostscript output that is larger than the input
+        let fake = FakeBackend::new(|_, args| {
+            let out = args
+                .iter()
+                .find_map(|a| a.strip_prefix("-sOutputFile="))
+                .unwrap();
+            let mut bytes = std::fs::read(args.last().unwrap()).unwrap();
+            bytes.extend_from_slice(b"\n% padding\n");
+            std::fs::write(out, bytes).unwrap();
+            ToolOutput {
+                code: Some(0),
+                ..Default::default()
+            }
+        });
+        let runner = ToolRunner::new(fake);
+        let input_size = std::fs::metadata(&input).unwrap().len();
+
+        let report =
+            compress_pdf_with(&runner, &input, &output, &CompressOptions::default()).unwrap();
+        assert_eq!(report.input_size, input_size);
+        assert_eq!(report.output_size, input_size + 11);
+        assert!(report.ratio > 1.0);
+        assert_eq!(report.pages, 3);
+        assert!(!report.kept_original);
+
+        let options = CompressOptions {
+            keep_original: true,
+            ..Default::default()
+        };
+        let report = compress_pdf_with(&runner, &input, &output, &options).unwrap();
+        assert!(report.kept_original);
+        assert_eq!(report.ratio, 1.0);
+        assert_eq!(
+            std::fs::read(&output).unwrap(),
+            std::fs::read(&input).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_compress_to_size_keeps_first_fit() {
+        let input = temp_path("compress_size_in.pdf");
+        let output = temp_path("compress_size_out.pdf");
+        sample_pdf(&input, 1);
+        // Each setting produces a smaller file than the previous one: 4000, 3500, …
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = calls.clone();
+        let fake = FakeBackend::new(move |_, args| {
+            let size = 4000 - 500 * counter.fetch_add(1, Ordering::SeqCst);
+            let out = args
+                .iter()
+                .find_map(|a| a.strip_prefix("-sOutputFile="))
+                .unwrap();
+            std::fs::write(out, vec![b'x'; size as usize]).unwrap();
+            ToolOutput {
+                code: Some(0),
+                ..Default::default()
+            }
+        });
+
+        let fit = compress_to_size(&ToolRunner::new(fake), &input, &output, 2600).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(fit.report.output_size, 2500);
+        assert_eq!(
+            fit.report.input_size,
+            std::fs::metadata(&input).unwrap().len()
+        );
+        assert_eq!(fit.options, size_ladder()[3]);
+        assert_eq!(std::fs::metadata(&output).unwrap().len(), 2500);
+        assert!(!output.with_extension("size-tmp.pdf").exists());
+
+        // The fake Ghostscript copies the input, which never gets under 10 bytes.
+        let err = compress_to_size(
+            &ToolRunner::new(FakeBackend::succeeding()),
+            &input,
+            &temp_path("compress_size_none.pdf"),
+            10,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("smallest result"));
+        assert!(!temp_path("compress_size_none.pdf").exists());
+    }
 }
@@ -1,37 +1,49 @@
 use std::path::Path;
-use std::process::Command;
 
-use crate::{gs_command, gs_lib_path};
+use crate::{PdfToolError, ToolRunner, page_count, validate_pages};
 
-fn gs_device(format: &str) -> Result<&'static str, String> {
+fn gs_device(format: &str) -> Result<&'static str, PdfToolError> {
     match format {
         "png" => Ok("png16m"),
         "jpeg" | "jpg" => Ok("jpeg"),
-        _ => Err(format!("Unsupported format '{}'. Must be: png, jpeg", format)),
+        _ => Err(PdfToolError::InvalidArgument(format!(
+            "Unsupported format '{}'. Must be: png, jpeg",
+            format
+        ))),
     }
 }
 
+/// Render pages of a PDF to images in `output_dir`.
+/// `pages` selects which pages to render (None = every page).
 pub fn convert_pdf(
+    runner: &ToolRunner,
     input: &Path,
     output_dir: &Path,
     format: &str,
     dpi: u32,
-) -> Result<(), Box<dyn std::error::Error>> {
+    pages: Option<&[u32]>,
+) -> Result<(), PdfToolError> {
     if !input.exists() {
-        return Err(format!("Input file not found: {}", input.display()).into());
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
     }
 
     let device = gs_device(format)?;
 
+    if let Some(pages) = pages {
+        if pages.is_empty() {
+            return Err(PdfToolError::InvalidArgument(
+                "No pages selected".to_string(),
+            ));
+        }
+        validate_pages(pages, page_count(input)?)?;
+    }
+
     let extension = match format {
         "jpg" => "jpeg",
         other => other,
     };
 
-    let stem = input
-        .file_stem()
-        .unwrap_or_default()
-        .to_string_lossy();
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
 
     std::fs::create_dir_all(output_dir)?;
 
@@ -40,37 +52,100 @@ pub fn convert_pdf(
         .display()
         .to_string();
 
-    let mut cmd = Command::new(gs_command());
+    let mut args = Vec::new();
+    if let Some(pages) = pages {
+        let page_list: Vec<String> = pages.iter().map(|p| p.to_string()).collect();
+        args.push(format!("-sPageList={}", page_list.join(",")));
+    }
+    args.extend([
+        format!("-sDEVICE={}", device),
+        format!("-r{}", dpi),
+        "-dNOPAUSE".to_string(),
+        "-dBATCH".to_string(),
+        "-dSAFER".to_string(),
+        format!("-sOutputFile={}", output_pattern),
+        input.display().to_string(),
+    ]);
 
-    if let Some(gs_dir) = gs_lib_path() {
-        let search_path = format!(
-            "{};{};{}",
-            gs_dir.join("lib").display(),
-            gs_dir.join("Resource").display(),
-            gs_dir.join("iccprofiles").display(),
-        );
-        cmd.env("GS_LIB", &search_path);
+    let total = match pages {
+        Some(pages) => pages.len() as u32,
+        None => page_count(input).unwrap_or(0),
+    };
+    // Ghostscript numbers the images from 1. On failure only the images this run
+    // created are removed, not those left by an earlier conversion.
+    let image = |number: u32| output_dir.join(format!("{}_{:03}.{}", stem, number, extension));
+    let existing: Vec<bool> = (1..=total).map(|number| image(number).exists()).collect();
+    if let Err(e) = runner.run_gs_pages(&args, total) {
+        for (number, existed) in (1..=total).zip(existing) {
+            if !existed {
+                let _ = std::fs::remove_file(image(number));
+            }
+        }
+        return Err(e);
     }
 
-    let status = cmd
-        .args([
-            &format!("-sDEVICE={}", device),
-            &format!("-r{}", dpi),
-            "-dNOPAUSE",
-            "-dBATCH",
-            "-dSAFER",
-            &format!("-sOutputFile={}", output_pattern),
-            &input.display().to_string(),
-        ])
-        .status()?;
-
-    if !status.success() {
-        return Err(format!(
-            "Ghostscript exited with code: {}",
-            status.code().unwrap_or(-1)
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolOutput;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    #[test]
+    fn test_out_of_range_page_error() {
+        let input = temp_path("convert_range_in.pdf");
+        sample_pdf(&input, 3);
+        let runner = ToolRunner::default();
+        let err = convert_pdf(
+            &runner,
+            &input,
+            &temp_path("convert_range_out"),
+            "png",
+            72,
+            Some(&[4]),
         )
-        .into());
+        .unwrap_err();
+        assert!(err.to_string().contains("valid: 1-3"), "{}", err);
     }
 
-    Ok(())
+    #[test]
+    fn test_failure_keeps_earlier_images() {
+        let input = temp_path("convert_cleanup_in.pdf");
+        let output_dir = temp_path("convert_cleanup_out");
+        sample_pdf(&input, 3);
+        let _ = std::fs::remove_dir_all(&output_dir);
+        std::fs::create_dir_all(&output_dir).unwrap();
+        // Left by an earlier, complete conversion
+        for number in 2..=3 {
+            std::fs::write(
+                output_dir.join(format!("convert_cleanup_in_00{}.png", number)),
+                b"old",
+            )
+            .unwrap();
+        }
+
+        // Ghostscript writes the first two images, then fails.
+        let dir = output_dir.clone();
+        let fake = FakeBackend::new(move |_, _| {
+            for number in 1..=2 {
+                std::fs::write(
+                    dir.join(format!("convert_cleanup_in_00{}.png", number)),
+                    b"new",
+                )
+                .unwrap();
+            }
+            ToolOutput {
+                code: Some(1),
+                ..Default::default()
+            }
+        });
+        assert!(convert_pdf(&ToolRunner::new(fake), &input, &output_dir, "png", 72, None).is_err());
+
+        assert!(!output_dir.join("convert_cleanup_in_001.png").exists());
+        assert!(output_dir.join("convert_cleanup_in_002.png").exists());
+        assert!(output_dir.join("convert_cleanup_in_003.png").exists());
+    }
 }
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{PdfToolError, Tool, ToolRunner};
+
+/// How long a tool may take to print its version before it is considered broken.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What `check_environment` found out about the external tools.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvironmentReport {
+    pub tools: Vec<ToolStatus>,
+    pub features: Vec<FeatureStatus>,
+}
+
+impl EnvironmentReport {
+    /// Features that cannot be used with the tools found.
+    pub fn unavailable(&self) -> impl Iterator<Item = &FeatureStatus> {
+        self.features.iter().filter(|f| !f.available)
+    }
+}
+
+/// One external tool: where it is and whether it runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolStatus {
+    pub name: String,
+    /// Executable that would be used (None = not found)
+    pub path: Option<PathBuf>,
+    /// Why this executable was chosen, e.g. "bundled" or "found in PATH"
+    pub source: Option<String>,
+    /// First line of the tool's version output
+    pub version: Option<String>,
+    /// Why the tool could not be used, when it was found but failed to run
+    pub error: Option<String>,
+}
+
+impl ToolStatus {
+    pub fn works(&self) -> bool {
+        self.version.is_some()
+    }
+}
+
+/// A group of operations and whether the tools they need are available.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureStatus {
+    pub name: String,
+    pub available: bool,
+    /// What is missing, when the feature is unavailable
+    pub reason: Option<String>,
+}
+
+/// Locate every external tool, run it to read its version and work out
+/// which features can be used.
+pub fn check_environment(runner: &ToolRunner) -> EnvironmentReport {
+    let runner = runner.clone().with_timeout(VERSION_TIMEOUT);
+    let tools: Vec<ToolStatus> = Tool::ALL
+        .iter()
+        .map(|&tool| tool_status(&runner, tool))
+        .collect();
+    let works = |tool: Tool| tools.iter().any(|t| t.name == tool.name() && t.works());
+
+    let missing = |tool: Tool| format!("requires {}", tool.name());
+    let mut features = Vec::new();
+    let mut feature = |name: &str, available: bool, reason: String| {
+        features.push(FeatureStatus {
+            name: name.to_string(),
+            available,
+            reason: (!available).then_some(reason),
+        });
+    };
+
+    feature(
+        "Info, rotate, reorder (at the input's PDF version)",
+        true,
+        String::new(),
+    );
+    feature(
+        "Extract, split, merge, compress, convert to images, colour conversion, PDF/A, linearize, setting the PDF version of any output",
+        works(Tool::Ghostscript),
+        missing(Tool::Ghostscript),
+    );
+    feature(
+        "Extract text, PDF to Markdown",
+        works(Tool::Pdftotext),
+        missing(Tool::Pdftotext),
+    );
+    feature(
+        "Markdown to PDF",
+        works(Tool::Pandoc) && (works(Tool::Typst) || works(Tool::Pdflatex)),
+        if works(Tool::Pandoc) {
+            "requires typst or pdflatex".to_string()
+        } else {
+            missing(Tool::Pandoc)
+        },
+    );
+
+    EnvironmentReport { tools, features }
+}
+
+fn tool_status(runner: &ToolRunner, tool: Tool) -> ToolStatus {
+    let mut status = ToolStatus {
+        name: tool.name().to_string(),
+        path: None,
+        source: None,
+        version: None,
+        error: None,
+    };
+    let Some(located) = runner.locate(tool) else {
+        return status;
+    };
+    status.path = Some(located.path);
+    status.source = Some(located.source.to_string());
+
+    // pdftotext only knows -v; xpdf's build exits with 99 after printing it,
+    // so the exit code is ignored as long as something was printed.
+    let flag = if tool == Tool::Pdftotext {
+        "-v"
+    } else {
+        "--version"
+    };
+    match runner.output(tool, &[flag]) {
+        Ok(output) if !output.success() && tool != Tool::Pdftotext => {
+            status.error = Some(PdfToolError::tool_failed(tool.name(), &output).to_string());
+        }
+        Ok(output) => match first_line(&output.stdout).or_else(|| first_line(&output.stderr)) {
+            Some(line) => status.version = Some(line),
+            None => status.error = Some(format!("{} printed no version", tool.name())),
+        },
+        Err(e) => status.error = Some(e.to_string()),
+    }
+    status
+}
+
+fn first_line(bytes: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolOutput;
+    use crate::runner::fake::FakeBackend;
+
+    #[test]
+    fn test_versions_and_features() {
+        let fake = FakeBackend::new(|tool, _| match tool {
+            Tool::Ghostscript => ToolOutput {
+                code: Some(0),
+                stdout: b"10.02.1\n".to_vec(),
+                ..Default::default()
+            },
+            Tool::Pdftotext => ToolOutput {
+                code: Some(99),
+                stderr: b"pdftotext version 4.04\nCopyright 1996-2022\n".to_vec(),
+                ..Default::default()
+            },
+            _ => ToolOutput {
+                code: Some(127),
+                ..Default::default()
+            },
+        });
+        let report = check_environment(&ToolRunner::new(fake.clone()));
+
+        let gs = &report.tools[0];
+        assert_eq!(gs.version.as_deref(), Some("10.02.1"));
+        assert_eq!(gs.source.as_deref(), Some("found in PATH"));
+        assert_eq!(
+            report.tools[1].version.as_deref(),
+            Some("pdftotext version 4.04")
+        );
+        assert!(!report.tools[2].works());
+        assert_eq!(
+            report.tools[2].error.as_deref(),
+            Some("pandoc exited with code: 127")
+        );
+
+        let unavailable: Vec<&str> = report.unavailable().map(|f| f.name.as_str()).collect();
+        assert_eq!(unavailable, ["Markdown to PDF"]);
+        assert_eq!(
+            report.unavailable().next().unwrap().reason.as_deref(),
+            Some("requires pandoc")
+        );
+        assert!(
+            fake.calls()
+                .contains(&(Tool::Pdftotext, vec!["-v".to_string()]))
+        );
+    }
+}
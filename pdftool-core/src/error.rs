@@ -0,0 +1,248 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::ToolOutput;
+
+/// Everything that can go wrong in a pdftool operation.
+#[derive(Debug)]
+pub enum PdfToolError {
+    /// An input file does not exist
+    InputNotFound(PathBuf),
+    /// An external program (Ghostscript, pdftotext, Pandoc...) is not installed
+    /// or could not be started
+    ToolNotFound {
+        tool: String,
+        /// How to install it, when we know
+        hint: Option<String>,
+    },
+    /// A parameter is malformed or out of range (page range, quality, format...)
+    InvalidArgument(String),
+    /// A file could not be read as a PDF
+    InvalidPdf(String),
+    /// An external program ran but reported an error
+    ToolFailed {
+        tool: String,
+        /// Exit code, or None if the process was killed by a signal
+        code: Option<i32>,
+        /// The last lines the program wrote to stderr (or stdout if stderr was empty)
+        stderr: String,
+    },
+    /// An external program was killed because it ran for too long
+    Timeout {
+        tool: String,
+        after: Duration,
+    },
+    /// The operation was stopped through its `CancelToken`
+    Cancelled,
+    Io(io::Error),
+}
+
+impl PdfToolError {
+    /// Short machine-readable name of the variant, used by the GUI and JSON output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PdfToolError::InputNotFound(_) => "input_not_found",
+            PdfToolError::ToolNotFound { .. } => "tool_not_found",
+            PdfToolError::InvalidArgument(_) => "invalid_argument",
+            PdfToolError::InvalidPdf(_) => "invalid_pdf",
+            PdfToolError::ToolFailed { .. } => "tool_failed",
+            PdfToolError::Timeout { .. } => "timeout",
+            PdfToolError::Cancelled => "cancelled",
+            PdfToolError::Io(_) => "io",
+        }
+    }
+
+    /// Build a `ToolFailed` from the captured output of a program that exited with an error.
+    pub(crate) fn tool_failed(tool: &str, output: &ToolOutput) -> Self {
+        // Ghostscript reports most errors on stdout, so fall back to it.
+        let stderr = match output_tail(&output.stderr) {
+            tail if tail.is_empty() => output_tail(&output.stdout),
+            tail => tail,
+        };
+        PdfToolError::ToolFailed {
+            tool: tool.to_string(),
+            code: output.code,
+            stderr,
+        }
+    }
+
+    /// Map an error from starting an external program: a missing executable
+    /// becomes `ToolNotFound`, anything else stays an I/O error.
+    pub(crate) fn spawn(tool: &str, error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            PdfToolError::ToolNotFound {
+                tool: tool.to_string(),
+                hint: None,
+            }
+        } else {
+            PdfToolError::Io(error)
+        }
+    }
+}
+
+impl fmt::Display for PdfToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdfToolError::InputNotFound(path) => {
+                write!(f, "Input file not found: {}", path.display())
+            }
+            PdfToolError::ToolNotFound { tool, hint } => {
+                write!(f, "{} not found", tool)?;
+                match hint {
+                    Some(hint) => write!(f, ". {}", hint),
+                    None => write!(f, " (is it installed and in PATH?)"),
+                }
+            }
+            PdfToolError::InvalidArgument(message) | PdfToolError::InvalidPdf(message) => {
+                write!(f, "{}", message)
+            }
+            PdfToolError::ToolFailed { tool, code, stderr } => {
+                write!(f, "{}", tool_failed_summary(tool, *code))?;
+                if !stderr.is_empty() {
+                    write!(f, "\n{}", stderr)?;
+                }
+                Ok(())
+            }
+            PdfToolError::Timeout { tool, after } => {
+                write!(
+                    f,
+                    "{} did not finish within {} seconds",
+                    tool,
+                    after.as_secs()
+                )
+            }
+            PdfToolError::Cancelled => write!(f, "Operation cancelled"),
+            PdfToolError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for PdfToolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PdfToolError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PdfToolError {
+    fn from(error: io::Error) -> Self {
+        PdfToolError::Io(error)
+    }
+}
+
+impl From<lopdf::Error> for PdfToolError {
+    fn from(error: lopdf::Error) -> Self {
+        match error {
+            lopdf::Error::IO(error) => PdfToolError::Io(error),
+            other => PdfToolError::InvalidPdf(other.to_string()),
+        }
+    }
+}
+
+fn tool_failed_summary(tool: &str, code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("{} exited with code: {}", tool, code),
+        None => format!("{} was terminated before finishing", tool),
+    }
+}
+
+/// How many lines of a failing program's output are kept in the error.
+const TAIL_LINES: usize = 15;
+
+/// The last non-empty lines of a program's output.
+fn output_tail(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(TAIL_LINES)..].join("\n")
+}
+
+/// Serialized as `{ kind, message, code, stderr }` so front ends can react to the kind
+/// and show the program output separately from the message.
+impl Serialize for PdfToolError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (message, code, stderr) = match self {
+            PdfToolError::ToolFailed { tool, code, stderr } => (
+                tool_failed_summary(tool, *code),
+                *code,
+                Some(stderr.as_str()),
+            ),
+            _ => (self.to_string(), None, None),
+        };
+        let mut state = serializer.serialize_struct("PdfToolError", 4)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &message)?;
+        state.serialize_field("code", &code)?;
+        state.serialize_field("stderr", &stderr)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_executable_is_tool_not_found() {
+        let error = PdfToolError::spawn("Ghostscript", io::Error::from(io::ErrorKind::NotFound));
+        assert_eq!(error.kind(), "tool_not_found");
+        assert!(error.to_string().starts_with("Ghostscript not found"));
+
+        let error = PdfToolError::spawn(
+            "Ghostscript",
+            io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+        assert_eq!(error.kind(), "io");
+    }
+
+    #[test]
+    fn test_tool_failed_message() {
+        let error = PdfToolError::ToolFailed {
+            tool: "Ghostscript".to_string(),
+            code: Some(1),
+            stderr: String::new(),
+        };
+        assert_eq!(error.to_string(), "Ghostscript exited with code: 1");
+    }
+
+    #[test]
+    fn test_output_tail_keeps_last_lines() {
+        let text: String = (1..=40).map(|i| format!("line {}\n\n", i)).collect();
+        let tail = output_tail(text.as_bytes());
+        assert_eq!(tail.lines().count(), TAIL_LINES);
+        assert!(tail.ends_with("line 40"));
+        assert!(tail.starts_with("line 26"));
+    }
+
+    #[test]
+    fn test_tool_failed_captures_output() {
+        let output = ToolOutput {
+            code: Some(3),
+            stdout: b"progress\n".to_vec(),
+            stderr: b"Error: /undefined\n".to_vec(),
+        };
+        let error = PdfToolError::tool_failed("Ghostscript", &output);
+        assert_eq!(
+            error.to_string(),
+            "Ghostscript exited with code: 3\nError: /undefined"
+        );
+
+        let output = ToolOutput {
+            code: Some(1),
+            stdout: b"Unrecoverable error\n".to_vec(),
+            stderr: Vec::new(),
+        };
+        let error = PdfToolError::tool_failed("Ghostscript", &output);
+        assert!(error.to_string().ends_with("Unrecoverable error"));
+    }
+}
@@ -1,55 +1,91 @@
 use std::path::Path;
-use std::process::Command;
 
-use crate::{gs_command, gs_lib_path};
+use crate::{PdfToolError, ToolRunner, discard_on_error, page_count, validate_pages};
 
+/// Copy the given pages of `input` into a new PDF.
+/// Pages are checked against the document before Ghostscript runs.
 pub fn extract_pages(
+    runner: &ToolRunner,
     input: &Path,
     output: &Path,
     pages: &[u32],
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), PdfToolError> {
     if !input.exists() {
-        return Err(format!("Input file not found: {}", input.display()).into());
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
     }
 
+    if pages.is_empty() {
+        return Err(PdfToolError::InvalidArgument(
+            "No pages selected".to_string(),
+        ));
+    }
+    validate_pages(pages, page_count(input)?)?;
+
     let page_list: String = pages
         .iter()
         .map(|p| p.to_string())
         .collect::<Vec<_>>()
         .join(",");
 
-    let mut cmd = Command::new(gs_command());
-
-    // Set GS library search path if bundled
-    if let Some(gs_dir) = gs_lib_path() {
-        let search_path = format!(
-            "{};{};{}",
-            gs_dir.join("lib").display(),
-            gs_dir.join("Resource").display(),
-            gs_dir.join("iccprofiles").display(),
-        );
-        cmd.env("GS_LIB", &search_path);
+    runner.check_version_loss(input);
+    let mut args = runner.pdfwrite_args();
+    args.extend([
+        "-dNOPAUSE".to_string(),
+        "-dBATCH".to_string(),
+        "-dSAFER".to_string(),
+        format!("-sPageList={}", page_list),
+        format!("-sOutputFile={}", output.display()),
+        input.display().to_string(),
+    ]);
+    let result = runner.run_gs_pages(&args, pages.len() as u32);
+    discard_on_error(result, output)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tool;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    #[test]
+    fn test_ghostscript_arguments() {
+        let input = temp_path("extract_args_in.pdf");
+        let output = temp_path("extract_args_out.pdf");
+        sample_pdf(&input, 4);
+        let fake = FakeBackend::succeeding();
+        extract_pages(&ToolRunner::new(fake.clone()), &input, &output, &[2, 3]).unwrap();
+
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 1);
+        let (tool, args) = &calls[0];
+        assert_eq!(*tool, Tool::Ghostscript);
+        assert!(args.contains(&"-sPageList=2,3".to_string()));
+        assert!(args.contains(&format!("-sOutputFile={}", output.display())));
+        assert_eq!(args.last().unwrap(), &input.display().to_string());
     }
 
-    let status = cmd
-        .args([
-            "-sDEVICE=pdfwrite",
-            "-dNOPAUSE",
-            "-dBATCH",
-            "-dSAFER",
-            &format!("-sPageList={}", page_list),
-            &format!("-sOutputFile={}", output.display()),
-            &input.display().to_string(),
-        ])
-        .status()?;
-
-    if !status.success() {
-        return Err(format!(
-            "Ghostscript exited with code: {}",
-            status.code().unwrap_or(-1)
+    #[test]
+    fn test_out_of_range_page_error() {
+        let input = temp_path("extract_range_in.pdf");
+        sample_pdf(&input, 10);
+        let err = extract_pages(
+            &ToolRunner::default(),
+            &input,
+            &temp_path("extract_range_out.pdf"),
+            &[2, 50],
         )
-        .into());
+        .unwrap_err();
+        assert!(err.to_string().contains("valid: 1-10"), "{}", err);
     }
 
-    Ok(())
+    #[test]
+    fn test_no_pages_error() {
+        let input = temp_path("extract_empty_in.pdf");
+        sample_pdf(&input, 2);
+        let runner = ToolRunner::default();
+        assert!(extract_pages(&runner, &input, &temp_path("extract_empty_out.pdf"), &[]).is_err());
+    }
 }
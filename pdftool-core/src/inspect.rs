@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use lopdf::{Dictionary, Document, Object};
+use serde::Serialize;
+
+use crate::rotate::page_rotation;
+use crate::{PdfToolError, inherited_attribute, is_linearized};
+
+/// What `inspect_pdf` found out about a document.
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfInfo {
+    pub path: PathBuf,
+    /// Size of the file on disk, in bytes
+    pub file_size: u64,
+    /// PDF version, e.g. "1.7" (the catalog's /Version wins over the header)
+    pub version: String,
+    pub page_count: u32,
+    pub pages: Vec<PageInfo>,
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    pub encrypted: bool,
+    /// Fonts referenced by the pages, sorted by name
+    pub fonts: Vec<FontInfo>,
+    /// Number of image XObjects stored in the file
+    pub image_count: usize,
+    /// Whether the file is linearized ("fast web view")
+    pub linearized: bool,
+}
+
+/// Size and orientation of one page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageInfo {
+    pub number: u32,
+    /// Width of the MediaBox in points (1/72 inch), before rotation
+    pub width: f32,
+    /// Height of the MediaBox in points, before rotation
+    pub height: f32,
+    /// Clockwise rotation in degrees: 0, 90, 180 or 270
+    pub rotation: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FontInfo {
+    pub name: String,
+    /// Font type: Type1, TrueType, Type0, Type3...
+    pub kind: String,
+    pub embedded: bool,
+}
+
+/// Read the structure of a PDF without modifying it.
+pub fn inspect_pdf(input: &Path) -> Result<PdfInfo, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    let file_size = std::fs::metadata(input)?.len();
+    let doc = Document::load(input).map_err(|e| {
+        PdfToolError::InvalidPdf(format!("Could not read PDF {}: {}", input.display(), e))
+    })?;
+
+    let page_ids = doc.get_pages();
+    let pages = page_ids
+        .iter()
+        .map(|(&number, &id)| {
+            let (width, height) = page_size(&doc, id);
+            PageInfo {
+                number,
+                width,
+                height,
+                rotation: page_rotation(&doc, id),
+            }
+        })
+        .collect();
+
+    let mut fonts = BTreeMap::new();
+    for &id in page_ids.values() {
+        collect_fonts(&doc, id, &mut fonts);
+    }
+
+    let image_count = images(&doc).count();
+
+    Ok(PdfInfo {
+        path: input.to_path_buf(),
+        file_size,
+        version: pdf_version(&doc),
+        page_count: page_ids.len() as u32,
+        pages,
+        title: info_string(&doc, b"Title"),
+        author: info_string(&doc, b"Author"),
+        creator: info_string(&doc, b"Creator"),
+        producer: info_string(&doc, b"Producer"),
+        encrypted: doc.is_encrypted() || doc.was_encrypted(),
+        fonts: fonts.into_values().collect(),
+        image_count,
+        linearized: is_linearized(input)?,
+    })
+}
+
+/// Image XObjects stored in the file.
+pub(crate) fn images(doc: &Document) -> impl Iterator<Item = &lopdf::Stream> {
+    doc.objects
+        .values()
+        .filter_map(|object| object.as_stream().ok())
+        .filter(|stream| {
+            stream
+                .dict
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .is_ok_and(|n| n == b"Image")
+        })
+}
+
+/// The header version, unless the catalog declares a later one (PDF 1.4+ allows this).
+fn pdf_version(doc: &Document) -> String {
+    let catalog_version = doc
+        .catalog()
+        .ok()
+        .and_then(|c| c.get(b"Version").and_then(Object::as_name).ok())
+        .map(|v| String::from_utf8_lossy(v).to_string());
+    match catalog_version {
+        Some(v) if v.as_str() > doc.version.as_str() => v,
+        _ => doc.version.clone(),
+    }
+}
+
+fn info_string(doc: &Document, key: &[u8]) -> Option<String> {
+    let info = match doc.trailer.get(b"Info").ok()? {
+        Object::Reference(id) => doc.get_dictionary(*id).ok()?,
+        Object::Dictionary(dict) => dict,
+        _ => return None,
+    };
+    let value = lopdf::decode_text_string(info.get(key).ok()?).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Width and height of the page's MediaBox, in points.
+fn page_size(doc: &Document, page_id: lopdf::ObjectId) -> (f32, f32) {
+    let media_box = inherited_attribute(doc, page_id, b"MediaBox")
+        .and_then(|obj| resolve(doc, obj).as_array().ok())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|v| resolve(doc, v).as_float().ok())
+                .collect::<Vec<f32>>()
+        });
+    match media_box.as_deref() {
+        Some([x0, y0, x1, y1]) => ((x1 - x0).abs(), (y1 - y0).abs()),
+        // US Letter is the default when the MediaBox is missing or malformed.
+        _ => (612.0, 792.0),
+    }
+}
+
+fn collect_fonts(doc: &Document, page_id: lopdf::ObjectId, fonts: &mut BTreeMap<String, FontInfo>) {
+    let Some(resources) = inherited_attribute(doc, page_id, b"Resources")
+        .and_then(|obj| resolve(doc, obj).as_dict().ok())
+    else {
+        return;
+    };
+    let Ok(font_dict) = resources
+        .get(b"Font")
+        .and_then(|obj| resolve(doc, obj).as_dict())
+    else {
+        return;
+    };
+
+    for value in font_dict.iter().map(|(_, v)| v) {
+        let Ok(font) = resolve(doc, value).as_dict() else {
+            continue;
+        };
+        let info = font_info(doc, font);
+        fonts.entry(info.name.clone()).or_insert(info);
+    }
+}
+
+fn font_info(doc: &Document, font: &Dictionary) -> FontInfo {
+    let name_of = |key: &[u8]| {
+        font.get(key)
+            .and_then(Object::as_name)
+            .map(|n| String::from_utf8_lossy(n).to_string())
+            .ok()
+    };
+    let kind = name_of(b"Subtype").unwrap_or_else(|| "Unknown".to_string());
+
+    // Composite fonts keep their descriptor on the descendant font.
+    let descriptor_owner = if kind == "Type0" {
+        font.get(b"DescendantFonts")
+            .and_then(|obj| resolve(doc, obj).as_array())
+            .ok()
+            .and_then(|fonts| fonts.first())
+            .and_then(|obj| resolve(doc, obj).as_dict().ok())
+            .unwrap_or(font)
+    } else {
+        font
+    };
+    let embedded = kind == "Type3"
+        || descriptor_owner
+            .get(b"FontDescriptor")
+            .and_then(|obj| resolve(doc, obj).as_dict())
+            .is_ok_and(|d| d.has(b"FontFile") || d.has(b"FontFile2") || d.has(b"FontFile3"));
+
+    FontInfo {
+        name: name_of(b"BaseFont").unwrap_or_else(|| "(unnamed)".to_string()),
+        kind,
+        embedded,
+    }
+}
+
+/// Follow a reference to the object it points to; other objects are returned as is.
+fn resolve<'a>(doc: &'a Document, obj: &'a Object) -> &'a Object {
+    match obj {
+        Object::Reference(id) => doc.get_object(*id).unwrap_or(obj),
+        _ => obj,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotate_pages;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    #[test]
+    fn test_inspect_sample() {
+        let input = temp_path("inspect_sample.pdf");
+        sample_pdf(&input, 3);
+
+        let info = inspect_pdf(&input).unwrap();
+        assert_eq!(info.page_count, 3);
+        assert_eq!(info.pages.len(), 3);
+        assert_eq!((info.pages[0].width, info.pages[0].height), (595.0, 842.0));
+        assert_eq!(info.version, "1.5");
+        assert!(!info.encrypted);
+        assert_eq!(info.image_count, 0);
+        assert!(!info.linearized);
+        assert_eq!(info.file_size, std::fs::metadata(&input).unwrap().len());
+        assert_eq!(
+            info.fonts,
+            vec![FontInfo {
+                name: "Helvetica".to_string(),
+                kind: "Type1".to_string(),
+                embedded: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_inspect_rotation() {
+        let input = temp_path("inspect_rot_in.pdf");
+        let rotated = temp_path("inspect_rot_out.pdf");
+        sample_pdf(&input, 2);
+        rotate_pages(
+            &crate::ToolRunner::default(),
+            &input,
+            &rotated,
+            Some(&[2]),
+            90,
+        )
+        .unwrap();
+
+        let info = inspect_pdf(&rotated).unwrap();
+        let rotations: Vec<i64> = info.pages.iter().map(|p| p.rotation).collect();
+        assert_eq!(rotations, vec![0, 90]);
+    }
+
+    #[test]
+    fn test_missing_file_error() {
+        assert!(inspect_pdf(&temp_path("inspect_missing.pdf")).is_err());
+    }
+}
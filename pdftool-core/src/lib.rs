@@ -1,138 +1,106 @@
-mod extract;
+mod batch;
+mod cancel;
+mod color;
 mod compress;
 mod convert;
-mod text_extract;
+mod doctor;
+mod error;
+mod extract;
+mod inspect;
+mod linearize;
 mod md_to_pdf;
+mod merge;
+mod page_range;
 mod pdf_to_md;
+mod pdfa;
+mod pipeline;
+mod progress;
+mod reorder;
+mod rotate;
+mod runner;
+mod split;
+mod text_extract;
+mod tools;
+mod version;
+mod watch;
 
-pub use extract::extract_pages;
-pub use compress::compress_pdf;
+#[cfg(test)]
+mod test_pdf;
+
+pub use batch::{expand_inputs, render_output_name, run_batch};
+pub use cancel::CancelToken;
+pub use color::{ColorMode, convert_color};
+pub use compress::{
+    CompressOptions, CompressReport, ImageStats, SizeFit, compress_pdf, compress_pdf_with,
+    compress_to_size,
+};
 pub use convert::convert_pdf;
-pub use text_extract::extract_text;
+pub use doctor::{EnvironmentReport, FeatureStatus, ToolStatus, check_environment};
+pub use error::PdfToolError;
+pub use extract::extract_pages;
+pub use inspect::{FontInfo, PageInfo, PdfInfo, inspect_pdf};
+pub use linearize::{is_linearized, linearize_pdf};
 pub use md_to_pdf::md_to_pdf;
+pub use merge::{MergeInput, merge_pdfs, parse_merge_input};
+pub use page_range::{
+    PageSelection, parse_page_range, parse_page_sequence, resolve_page_range,
+    resolve_page_sequence, validate_pages,
+};
 pub use pdf_to_md::pdf_to_md;
+pub use pdfa::{PdfaLevel, to_pdfa};
+pub use pipeline::{Pipeline, Step};
+pub use progress::Progress;
+pub use reorder::{ReorderMode, reorder_pages};
+pub use rotate::rotate_pages;
+pub use runner::{Backend, Invocation, LineHook, SystemBackend, ToolOutput, ToolRunner};
+pub use split::{DEFAULT_SPLIT_TEMPLATE, SplitMode, split_pdf};
+pub use text_extract::extract_text;
+pub use tools::{Located, Tool, ToolSource};
+pub use version::{PdfVersion, set_pdf_version, version_warnings};
+pub use watch::{WatchOptions, watch_folder};
 
-use std::path::PathBuf;
+use std::path::Path;
 
-/// Return the path to the Ghostscript executable.
-/// Priority:
-/// 1. Bundled GS next to the current executable (ghostscript/bin/gswin64c.exe)
-/// 2. System-installed GS in PATH
-pub fn gs_command() -> String {
-    if cfg!(target_os = "windows") {
-        // Look for bundled Ghostscript next to our executable
-        if let Ok(exe) = std::env::current_exe() {
-            if let Some(exe_dir) = exe.parent() {
-                let bundled = exe_dir.join("ghostscript").join("bin").join("gswin64c.exe");
-                if bundled.exists() {
-                    return bundled.to_string_lossy().to_string();
-                }
-            }
-        }
-        "gswin64c".to_string()
-    } else {
-        "gs".to_string()
+/// Return the number of pages in a PDF.
+/// Only the cross-reference table and page tree are parsed, not the page contents.
+pub fn page_count(input: &Path) -> Result<u32, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
     }
+    let metadata = lopdf::Document::load_metadata(input).map_err(|e| {
+        PdfToolError::InvalidPdf(format!("Could not read PDF {}: {}", input.display(), e))
+    })?;
+    Ok(metadata.page_count)
 }
 
-/// Return the path to the bundled GS lib directory, if it exists.
-/// Ghostscript needs this to find its init files.
-pub fn gs_lib_path() -> Option<PathBuf> {
-    if let Ok(exe) = std::env::current_exe() {
-        if let Some(exe_dir) = exe.parent() {
-            let gs_dir = exe_dir.join("ghostscript");
-            let lib_dir = gs_dir.join("lib");
-            let res_dir = gs_dir.join("Resource");
-            if lib_dir.exists() && res_dir.exists() {
-                return Some(gs_dir);
-            }
-        }
+/// Delete an output a failed, timed-out or cancelled tool may have left half-written.
+pub(crate) fn discard_on_error<T>(
+    result: Result<T, PdfToolError>,
+    output: &Path,
+) -> Result<T, PdfToolError> {
+    if result.is_err() {
+        let _ = std::fs::remove_file(output);
     }
-    None
+    result
 }
 
-/// Parse a page range string into a sorted, deduplicated list of page numbers.
-pub fn parse_page_range(pages: &str) -> Result<Vec<u32>, String> {
-    let mut result = Vec::new();
-
-    for part in pages.split(',') {
-        let part = part.trim();
-        if part.contains('-') {
-            let bounds: Vec<&str> = part.splitn(2, '-').collect();
-            let start: u32 = bounds[0]
-                .trim()
-                .parse()
-                .map_err(|_| format!("Invalid range start: '{}'", bounds[0].trim()))?;
-            let end: u32 = bounds[1]
-                .trim()
-                .parse()
-                .map_err(|_| format!("Invalid range end: '{}'", bounds[1].trim()))?;
-            if start == 0 || end == 0 {
-                return Err("Page numbers must be greater than 0".to_string());
-            }
-            if start > end {
-                return Err(format!("Invalid range: {}-{} (start > end)", start, end));
-            }
-            for page in start..=end {
-                result.push(page);
-            }
-        } else {
-            let page: u32 = part
-                .parse()
-                .map_err(|_| format!("Invalid page number: '{}'", part))?;
-            if page == 0 {
-                return Err("Page numbers must be greater than 0".to_string());
-            }
-            result.push(page);
+/// Look up an inheritable page attribute (Resources, MediaBox, CropBox, Rotate),
+/// walking up the page tree when the page itself does not define it.
+pub(crate) fn inherited_attribute<'a>(
+    doc: &'a lopdf::Document,
+    page_id: lopdf::ObjectId,
+    key: &[u8],
+) -> Option<&'a lopdf::Object> {
+    let mut current = doc.get_dictionary(page_id).ok();
+    while let Some(dict) = current {
+        if let Ok(value) = dict.get(key) {
+            return Some(value);
         }
+        current = dict
+            .get(b"Parent")
+            .and_then(lopdf::Object::as_reference)
+            .and_then(|id| doc.get_dictionary(id))
+            .ok();
     }
-
-    result.sort();
-    result.dedup();
-    Ok(result)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_single_page() {
-        assert_eq!(parse_page_range("3").unwrap(), vec![3]);
-    }
-
-    #[test]
-    fn test_range() {
-        assert_eq!(parse_page_range("2-5").unwrap(), vec![2, 3, 4, 5]);
-    }
-
-    #[test]
-    fn test_comma_separated() {
-        assert_eq!(parse_page_range("1,3,5").unwrap(), vec![1, 3, 5]);
-    }
-
-    #[test]
-    fn test_mixed() {
-        assert_eq!(parse_page_range("1,3-5,8").unwrap(), vec![1, 3, 4, 5, 8]);
-    }
-
-    #[test]
-    fn test_dedup() {
-        assert_eq!(parse_page_range("1,1,2").unwrap(), vec![1, 2]);
-    }
-
-    #[test]
-    fn test_zero_page_error() {
-        assert!(parse_page_range("0").is_err());
-    }
-
-    #[test]
-    fn test_invalid_range_error() {
-        assert!(parse_page_range("5-2").is_err());
-    }
-
-    #[test]
-    fn test_invalid_input_error() {
-        assert!(parse_page_range("abc").is_err());
-    }
+    None
 }
@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::compress::run_gs_to_pdf;
+use crate::{PdfToolError, ToolRunner, page_count};
+
+/// The linearization dictionary must start within the first 1024 bytes.
+const HEADER_LEN: u64 = 1024;
+
+/// Rewrite a PDF as linearized ("fast web view"), so browsers can show the first
+/// page before the whole file is downloaded.
+pub fn linearize_pdf(runner: &ToolRunner, input: &Path, output: &Path) -> Result<(), PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    runner.check_version_loss(input);
+    let mut args = runner.pdfwrite_args();
+    args.extend(["-dFastWebView=true", "-dNOPAUSE", "-dBATCH", "-dSAFER"].map(String::from));
+    let total = page_count(input).unwrap_or(0);
+    run_gs_to_pdf(runner, input, output, args, None, total)
+}
+
+/// Whether a PDF is linearized. A file that was linearized and then changed
+/// (by an incremental update, for instance) no longer counts.
+pub fn is_linearized(input: &Path) -> Result<bool, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+    let file = File::open(input)?;
+    let size = file.metadata()?.len();
+    let mut header = Vec::new();
+    file.take(HEADER_LEN).read_to_end(&mut header)?;
+    Ok(linearized_header(&header, size))
+}
+
+/// Check the first object of the file for a linearization dictionary whose
+/// recorded file length (/L) matches `size`.
+pub(crate) fn linearized_header(header: &[u8], size: u64) -> bool {
+    let text = String::from_utf8_lossy(header);
+    let Some(start) = text.find(" obj") else {
+        return false;
+    };
+    let object = &text[start..];
+    let object = &object[..object.find("endobj").unwrap_or(object.len())];
+    if !object.contains("/Linearized") {
+        return false;
+    }
+
+    // "/L 12345", not "/Linearized"
+    let length = object.match_indices("/L").find_map(|(i, _)| {
+        let rest = &object[i + 2..];
+        if !rest.starts_with(|c: char| c.is_ascii_whitespace()) {
+            return None;
+        }
+        let digits: String = rest
+            .trim_start()
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect();
+        digits.parse::<u64>().ok()
+    });
+    length == Some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    const HEADER: &[u8] =
+        b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n4 0 obj\n<< /Linearized 1 /L 48213 /H [ 560 140 ] /O 6 /E 17500 /N 2 /T 47900 >>\nendobj\n";
+
+    #[test]
+    fn test_linearized_header() {
+        assert!(linearized_header(HEADER, 48213));
+        // Changed since it was linearized
+        assert!(!linearized_header(HEADER, 50000));
+
+        let plain = b"%PDF-1.7\n1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n";
+        assert!(!linearized_header(plain, plain.len() as u64));
+    }
+
+    #[test]
+    fn test_linearize_asks_for_fast_web_view() {
+        let input = temp_path("linearize_in.pdf");
+        sample_pdf(&input, 2);
+        assert!(!is_linearized(&input).unwrap());
+
+        let fake = FakeBackend::succeeding();
+        linearize_pdf(
+            &ToolRunner::new(fake.clone()),
+            &input,
+            &temp_path("linearize_out.pdf"),
+        )
+        .unwrap();
+        assert!(
+            fake.calls()[0]
+                .1
+                .contains(&"-dFastWebView=true".to_string())
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+use crate::{PageSelection, PdfToolError, ToolRunner, discard_on_error, extract_pages, page_count};
+
+/// A document to merge, optionally restricted to some of its pages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeInput {
+    pub path: PathBuf,
+    /// Pages to keep (None = whole document)
+    pub pages: Option<PageSelection>,
+}
+
+impl From<PathBuf> for MergeInput {
+    fn from(path: PathBuf) -> Self {
+        MergeInput { path, pages: None }
+    }
+}
+
+/// Parse a merge input of the form `file.pdf` or `file.pdf:1-3`.
+/// The text after the last `:` is a page selection (see `PageSelection`) unless it
+/// contains a path separator, which keeps drive letters (`C:\...`) working.
+pub fn parse_merge_input(spec: &str) -> Result<MergeInput, PdfToolError> {
+    if !Path::new(spec).exists()
+        && let Some((path, range)) = spec.rsplit_once(':')
+        && !path.is_empty()
+        && !range.contains(['/', '\\'])
+    {
+        let pages = PageSelection::parse(range).map_err(|e| {
+            PdfToolError::InvalidArgument(format!("Invalid page range '{}': {}", range, e))
+        })?;
+        return Ok(MergeInput {
+            path: PathBuf::from(path),
+            pages: Some(pages),
+        });
+    }
+    Ok(MergeInput::from(PathBuf::from(spec)))
+}
+
+/// Concatenate several PDFs (or page selections of them) into one file.
+/// Inputs with a page selection are first extracted to temporary files
+/// next to the output, then everything is merged in a single Ghostscript pass.
+pub fn merge_pdfs(
+    runner: &ToolRunner,
+    inputs: &[MergeInput],
+    output: &Path,
+) -> Result<(), PdfToolError> {
+    if inputs.is_empty() {
+        return Err(PdfToolError::InvalidArgument(
+            "No input files to merge".to_string(),
+        ));
+    }
+
+    for input in inputs {
+        if !input.path.exists() {
+            return Err(PdfToolError::InputNotFound(input.path.clone()));
+        }
+        if input.path == output {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "Output file must differ from the inputs: {}",
+                output.display()
+            )));
+        }
+    }
+
+    let mut sources: Vec<PathBuf> = Vec::new();
+    let mut temp_files: Vec<PathBuf> = Vec::new();
+    // Pages in the merged document, for progress reporting
+    let mut total = 0;
+
+    let result = (|| -> Result<(), PdfToolError> {
+        for (i, input) in inputs.iter().enumerate() {
+            runner.check_version_loss(&input.path);
+            match &input.pages {
+                Some(selection) => {
+                    let pages = selection.pages(page_count(&input.path)?).map_err(|e| {
+                        PdfToolError::InvalidArgument(format!("{}: {}", input.path.display(), e))
+                    })?;
+                    let temp = output.with_extension(format!("part{}.pdf", i + 1));
+                    temp_files.push(temp.clone());
+                    extract_pages(
+                        &runner.without_progress().without_warnings(),
+                        &input.path,
+                        &temp,
+                        &pages,
+                    )?;
+                    sources.push(temp);
+                    total += pages.len() as u32;
+                }
+                None => {
+                    sources.push(input.path.clone());
+                    total += page_count(&input.path).unwrap_or(0);
+                }
+            }
+        }
+        run_merge(runner, &sources, output, total)
+    })();
+
+    for temp in &temp_files {
+        let _ = std::fs::remove_file(temp);
+    }
+
+    result
+}
+
+fn run_merge(
+    runner: &ToolRunner,
+    sources: &[PathBuf],
+    output: &Path,
+    total: u32,
+) -> Result<(), PdfToolError> {
+    let mut args = runner.pdfwrite_args();
+    args.extend([
+        "-dNOPAUSE".to_string(),
+        "-dBATCH".to_string(),
+        "-dSAFER".to_string(),
+        format!("-sOutputFile={}", output.display()),
+    ]);
+    args.extend(sources.iter().map(|source| source.display().to_string()));
+
+    discard_on_error(runner.run_gs_pages(&args, total), output)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    #[test]
+    fn test_plain_path() {
+        let input = parse_merge_input("a.pdf").unwrap();
+        assert_eq!(input.path, PathBuf::from("a.pdf"));
+        assert_eq!(input.pages, None);
+    }
+
+    #[test]
+    fn test_path_with_range() {
+        let input = parse_merge_input("b.pdf:1-3").unwrap();
+        assert_eq!(input.path, PathBuf::from("b.pdf"));
+        assert_eq!(input.pages.unwrap().pages(10).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_path_with_relative_range() {
+        let input = parse_merge_input("b.pdf:2-end,!4").unwrap();
+        assert_eq!(input.pages.unwrap().pages(5).unwrap(), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn test_drive_letter_is_not_a_range() {
+        let input = parse_merge_input(r"C:\docs\a.pdf").unwrap();
+        assert_eq!(input.path, PathBuf::from(r"C:\docs\a.pdf"));
+        assert_eq!(input.pages, None);
+
+        let input = parse_merge_input(r"C:\docs\a.pdf:2,4").unwrap();
+        assert_eq!(input.path, PathBuf::from(r"C:\docs\a.pdf"));
+        assert_eq!(input.pages.unwrap().pages(5).unwrap(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_invalid_range_error() {
+        assert!(parse_merge_input("b.pdf:x-2").is_err());
+    }
+
+    #[test]
+    fn test_merge_extracts_selections_first() {
+        let first = temp_path("merge_first.pdf");
+        let second = temp_path("merge_second.pdf");
+        let output = temp_path("merge_out.pdf");
+        sample_pdf(&first, 2);
+        sample_pdf(&second, 4);
+        let inputs = [
+            MergeInput::from(first.clone()),
+            parse_merge_input(&format!("{}:2-3", second.display())).unwrap(),
+        ];
+        let fake = FakeBackend::succeeding();
+        merge_pdfs(&ToolRunner::new(fake.clone()), &inputs, &output).unwrap();
+
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls[0].1.contains(&"-sPageList=2,3".to_string()));
+        let temp = output.with_extension("part2.pdf");
+        assert!(
+            calls[1]
+                .1
+                .ends_with(&[first.display().to_string(), temp.display().to_string()])
+        );
+        assert!(!temp.exists());
+    }
+}
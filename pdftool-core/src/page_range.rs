@@ -0,0 +1,395 @@
+use crate::PdfToolError;
+
+/// One end of a page range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bound {
+    /// A page number counted from the start (1 = first page)
+    Page(u32),
+    /// A page number counted from the end (1 = last page)
+    FromEnd(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Item {
+    Single(Bound),
+    /// `None` as the end means "up to the last page" (e.g. "5-")
+    Range(Bound, Option<Bound>),
+    Odd,
+    Even,
+}
+
+/// A parsed page selection such as "1,3-5", "2-end", "-3-", "odd" or "1-20,!7".
+///
+/// Items are separated by commas:
+/// - `N` — a page; `-N` counts from the end (`-1` is the last page)
+/// - `end` / `last` — the last page
+/// - `A-B` — a range; `A-` runs to the last page
+/// - `odd` / `even`
+/// - `!item` — remove those pages from the selection; a selection made only
+///   of exclusions starts from every page (`!1` = everything but the cover)
+///
+/// Selections that use `end`, negative pages, `odd`/`even` or exclusions need the
+/// document's page count to be resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageSelection {
+    included: Vec<Item>,
+    excluded: Vec<Item>,
+}
+
+impl PageSelection {
+    /// Parse a selection without resolving it against a document.
+    pub fn parse(spec: &str) -> Result<Self, PdfToolError> {
+        let mut included = Vec::new();
+        let mut excluded = Vec::new();
+
+        for part in spec.split(',') {
+            let part = part.trim();
+            match part.strip_prefix('!') {
+                Some(rest) => excluded.push(parse_item(rest.trim())?),
+                None => included.push(parse_item(part)?),
+            }
+        }
+
+        Ok(PageSelection { included, excluded })
+    }
+
+    /// True if the selection only uses literal page numbers and ranges.
+    pub fn is_literal(&self) -> bool {
+        self.excluded.is_empty()
+            && self.included.iter().all(|item| {
+                matches!(
+                    item,
+                    Item::Single(Bound::Page(_))
+                        | Item::Range(Bound::Page(_), Some(Bound::Page(_)))
+                )
+            })
+    }
+
+    /// Resolve to a sorted, deduplicated list of pages of a `total`-page document.
+    pub fn pages(&self, total: u32) -> Result<Vec<u32>, PdfToolError> {
+        let mut result = self.resolve(total, false)?;
+        result.sort();
+        result.dedup();
+        Ok(result)
+    }
+
+    /// Resolve to pages in the order written, keeping repeats.
+    /// Descending ranges such as "10-1" list pages backwards.
+    pub fn sequence(&self, total: u32) -> Result<Vec<u32>, PdfToolError> {
+        self.resolve(total, true)
+    }
+
+    fn resolve(&self, total: u32, allow_descending: bool) -> Result<Vec<u32>, PdfToolError> {
+        let mut result = Vec::new();
+        if self.included.is_empty() {
+            result.extend(1..=total);
+        }
+        for item in &self.included {
+            expand_item(item, total, allow_descending, &mut result)?;
+        }
+
+        let mut removed = Vec::new();
+        for item in &self.excluded {
+            expand_item(item, total, true, &mut removed)?;
+        }
+        result.retain(|page| !removed.contains(page));
+
+        Ok(result)
+    }
+}
+
+fn parse_item(part: &str) -> Result<Item, PdfToolError> {
+    match part.to_ascii_lowercase().as_str() {
+        "odd" => return Ok(Item::Odd),
+        "even" => return Ok(Item::Even),
+        _ => {}
+    }
+
+    // A leading '-' belongs to the first bound ("-3", "-3-end").
+    let search_from = usize::from(part.starts_with('-'));
+    match part[search_from..].find('-') {
+        Some(i) => {
+            let (start, end) = (&part[..search_from + i], &part[search_from + i + 1..]);
+            let start = parse_bound(start.trim())
+                .map_err(|_| invalid(format!("Invalid range start: '{}'", start.trim())))?;
+            let end = if end.trim().is_empty() {
+                None
+            } else {
+                Some(
+                    parse_bound(end.trim())
+                        .map_err(|_| invalid(format!("Invalid range end: '{}'", end.trim())))?,
+                )
+            };
+            if let (Bound::Page(a), Some(Bound::Page(b))) = (start, end)
+                && (a == 0 || b == 0)
+            {
+                return Err(invalid("Page numbers must be greater than 0".to_string()));
+            }
+            Ok(Item::Range(start, end))
+        }
+        None => {
+            let bound = parse_bound(part)
+                .map_err(|_| invalid(format!("Invalid page number: '{}'", part)))?;
+            if bound == Bound::Page(0) {
+                return Err(invalid("Page numbers must be greater than 0".to_string()));
+            }
+            Ok(Item::Single(bound))
+        }
+    }
+}
+
+fn parse_bound(text: &str) -> Result<Bound, ()> {
+    match text.to_ascii_lowercase().as_str() {
+        "end" | "last" => Ok(Bound::FromEnd(1)),
+        _ => match text.strip_prefix('-') {
+            Some(n) => match n.parse::<u32>() {
+                Ok(n) if n > 0 => Ok(Bound::FromEnd(n)),
+                _ => Err(()),
+            },
+            None => text.parse::<u32>().map(Bound::Page).map_err(|_| ()),
+        },
+    }
+}
+
+fn resolve_bound(bound: Bound, total: u32) -> Result<u32, PdfToolError> {
+    let page = match bound {
+        Bound::Page(0) => return Err(invalid("Page numbers must be greater than 0".to_string())),
+        Bound::Page(n) => n,
+        Bound::FromEnd(n) if n <= total => total - n + 1,
+        Bound::FromEnd(n) => return Err(out_of_range(&format!("-{}", n), total)),
+    };
+    if page > total {
+        return Err(out_of_range(&page.to_string(), total));
+    }
+    Ok(page)
+}
+
+fn out_of_range(page: &str, total: u32) -> PdfToolError {
+    invalid(match total {
+        0 => format!("Page {} is out of range: the document has no pages", page),
+        1 => format!(
+            "Page {} is out of range: the document has 1 page (valid: 1)",
+            page
+        ),
+        _ => format!(
+            "Page {} is out of range: the document has {} pages (valid: 1-{})",
+            page, total, total
+        ),
+    })
+}
+
+fn invalid(message: String) -> PdfToolError {
+    PdfToolError::InvalidArgument(message)
+}
+
+/// Check that every page exists in a document with `total` pages.
+/// The error names the first bad page and the valid range.
+pub fn validate_pages(pages: &[u32], total: u32) -> Result<(), PdfToolError> {
+    if pages.contains(&0) {
+        return Err(invalid("Page numbers must be greater than 0".to_string()));
+    }
+    match pages.iter().find(|&&p| p > total) {
+        Some(page) => Err(out_of_range(&page.to_string(), total)),
+        None => Ok(()),
+    }
+}
+
+fn expand_item(
+    item: &Item,
+    total: u32,
+    allow_descending: bool,
+    out: &mut Vec<u32>,
+) -> Result<(), PdfToolError> {
+    match item {
+        Item::Odd => out.extend((1..=total).step_by(2)),
+        Item::Even => out.extend((2..=total).step_by(2)),
+        Item::Single(bound) => out.push(resolve_bound(*bound, total)?),
+        Item::Range(start, end) => {
+            let start = resolve_bound(*start, total)?;
+            let end = match end {
+                Some(end) => resolve_bound(*end, total)?,
+                None => total,
+            };
+            if start <= end {
+                out.extend(start..=end);
+            } else if allow_descending {
+                out.extend((end..=start).rev());
+            } else {
+                return Err(invalid(format!(
+                    "Invalid range: {}-{} (start > end)",
+                    start, end
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parse a page range string into a sorted, deduplicated list of page numbers.
+/// Only literal pages and ranges are accepted; use `resolve_page_range` for
+/// `end`, negative pages, `odd`/`even` and exclusions.
+pub fn parse_page_range(pages: &str) -> Result<Vec<u32>, PdfToolError> {
+    parse_literal(pages)?.pages(u32::MAX)
+}
+
+/// Parse a page sequence, keeping the order and repeats as written (e.g. "5,1-4,5").
+/// Descending ranges such as "10-1" list pages backwards.
+pub fn parse_page_sequence(pages: &str) -> Result<Vec<u32>, PdfToolError> {
+    parse_literal(pages)?.sequence(u32::MAX)
+}
+
+/// Resolve a page range against a document with `total` pages.
+/// Returns a sorted, deduplicated list; see `PageSelection` for the syntax.
+pub fn resolve_page_range(pages: &str, total: u32) -> Result<Vec<u32>, PdfToolError> {
+    PageSelection::parse(pages)?.pages(total)
+}
+
+/// Resolve a page sequence against a document with `total` pages, keeping order and repeats.
+pub fn resolve_page_sequence(pages: &str, total: u32) -> Result<Vec<u32>, PdfToolError> {
+    PageSelection::parse(pages)?.sequence(total)
+}
+
+fn parse_literal(pages: &str) -> Result<PageSelection, PdfToolError> {
+    let selection = PageSelection::parse(pages)?;
+    if !selection.is_literal() {
+        return Err(invalid(format!(
+            "'{}' needs the document's page count (end, negative pages, odd/even, exclusions)",
+            pages.trim()
+        )));
+    }
+    Ok(selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_page() {
+        assert_eq!(parse_page_range("3").unwrap(), vec![3]);
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(parse_page_range("2-5").unwrap(), vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_comma_separated() {
+        assert_eq!(parse_page_range("1,3,5").unwrap(), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn test_mixed() {
+        assert_eq!(parse_page_range("1,3-5,8").unwrap(), vec![1, 3, 4, 5, 8]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        assert_eq!(parse_page_range("1,1,2").unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_zero_page_error() {
+        assert!(parse_page_range("0").is_err());
+    }
+
+    #[test]
+    fn test_invalid_range_error() {
+        assert!(parse_page_range("5-2").is_err());
+    }
+
+    #[test]
+    fn test_invalid_input_error() {
+        assert!(parse_page_range("abc").is_err());
+    }
+
+    #[test]
+    fn test_sequence_keeps_order_and_repeats() {
+        assert_eq!(
+            parse_page_sequence("5,1-4,5").unwrap(),
+            vec![5, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_sequence_descending_range() {
+        assert_eq!(parse_page_sequence("4-1").unwrap(), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_sequence_zero_page_error() {
+        assert!(parse_page_sequence("3,0").is_err());
+    }
+
+    #[test]
+    fn test_literal_parse_rejects_relative_syntax() {
+        assert!(parse_page_range("2-end").is_err());
+        assert!(parse_page_range("odd").is_err());
+    }
+
+    #[test]
+    fn test_end_and_last() {
+        assert_eq!(resolve_page_range("end", 10).unwrap(), vec![10]);
+        assert_eq!(resolve_page_range("8-last", 10).unwrap(), vec![8, 9, 10]);
+    }
+
+    #[test]
+    fn test_open_range() {
+        assert_eq!(resolve_page_range("5-", 7).unwrap(), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn test_negative_index() {
+        assert_eq!(resolve_page_range("-1", 10).unwrap(), vec![10]);
+        assert_eq!(resolve_page_range("-3", 10).unwrap(), vec![8]);
+        assert_eq!(resolve_page_range("-3-", 10).unwrap(), vec![8, 9, 10]);
+        assert_eq!(resolve_page_range("1,-2-end", 5).unwrap(), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_odd_even() {
+        assert_eq!(resolve_page_range("odd", 5).unwrap(), vec![1, 3, 5]);
+        assert_eq!(resolve_page_range("EVEN", 5).unwrap(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_exclusions() {
+        assert_eq!(
+            resolve_page_range("1-10,!7", 20).unwrap(),
+            vec![1, 2, 3, 4, 5, 6, 8, 9, 10]
+        );
+        assert_eq!(resolve_page_range("!1", 4).unwrap(), vec![2, 3, 4]);
+        assert_eq!(resolve_page_range("odd,!1,!end", 7).unwrap(), vec![3, 5]);
+    }
+
+    #[test]
+    fn test_resolved_sequence() {
+        assert_eq!(resolve_page_sequence("end-1", 3).unwrap(), vec![3, 2, 1]);
+        assert_eq!(resolve_page_sequence("-1,1-", 3).unwrap(), vec![3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_out_of_range_error() {
+        assert!(resolve_page_range("12", 10).is_err());
+        assert!(resolve_page_range("-11", 10).is_err());
+        assert!(resolve_page_range("5-12", 10).is_err());
+    }
+
+    #[test]
+    fn test_validate_pages() {
+        assert!(validate_pages(&[1, 5, 10], 10).is_ok());
+        assert!(validate_pages(&[0], 10).is_err());
+        let err = validate_pages(&[3, 50], 10).unwrap_err().to_string();
+        assert!(err.contains("Page 50"), "{}", err);
+        assert!(err.contains("1-10"), "{}", err);
+    }
+
+    #[test]
+    fn test_relative_invalid_input_error() {
+        assert!(resolve_page_range("-", 10).is_err());
+        assert!(resolve_page_range("-0", 10).is_err());
+        assert!(resolve_page_range("end-2", 10).is_err());
+        assert!(resolve_page_range("!", 10).is_err());
+    }
+}
@@ -1,29 +1,6 @@
 use std::path::Path;
-use std::process::Command;
-
-/// Find the pdftotext executable (same logic as text_extract.rs).
-fn pdftotext_command() -> String {
-    if cfg!(target_os = "windows") {
-        if let Ok(exe) = std::env::current_exe() {
-            if let Some(exe_dir) = exe.parent() {
-                let bundled = exe_dir.join("pdftotext.exe");
-                if bundled.exists() {
-                    return bundled.to_string_lossy().to_string();
-                }
-            }
-        }
-        let known_paths = [
-            r"C:\Program Files\Git\mingw64\bin\pdftotext.exe",
-            r"C:\Program Files (x86)\Git\mingw64\bin\pdftotext.exe",
-        ];
-        for path in &known_paths {
-            if Path::new(path).exists() {
-                return path.to_string();
-            }
-        }
-    }
-    "pdftotext".to_string()
-}
+
+use crate::{PdfToolError, Tool, ToolRunner};
 
 /// Returns true if the line looks like a numbered list item (e.g. "1)", "12.", "a)")
 fn is_list_item(line: &str) -> bool {
@@ -117,7 +94,7 @@ fn assign_heading_levels(lines: &[String]) -> Vec<u8> {
         let preceded_by_blank = i == 0
             || lines
                 .get(i.wrapping_sub(1))
-                .map_or(true, |l| l.trim().is_empty());
+                .is_none_or(|l| l.trim().is_empty());
 
         let is_short = len <= heading_threshold && len >= 3;
 
@@ -193,30 +170,26 @@ fn format_as_markdown(raw: &str) -> String {
 }
 
 /// Convert a PDF file to Markdown using pdftotext with UTF-8 encoding.
-pub fn pdf_to_md(input: &Path, output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+pub fn pdf_to_md(runner: &ToolRunner, input: &Path, output: &Path) -> Result<(), PdfToolError> {
     if !input.exists() {
-        return Err(format!("Input file not found: {}", input.display()).into());
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
     }
 
     let temp_txt = output.with_extension("tmp.txt");
 
-    let status = Command::new(pdftotext_command())
-        .args([
+    let result = runner.run(
+        Tool::Pdftotext,
+        &[
             "-enc",
             "UTF-8",
             "-layout",
             &input.display().to_string(),
             &temp_txt.display().to_string(),
-        ])
-        .status()?;
-
-    if !status.success() {
+        ],
+    );
+    if let Err(e) = result {
         let _ = std::fs::remove_file(&temp_txt);
-        return Err(format!(
-            "pdftotext exited with code: {}",
-            status.code().unwrap_or(-1)
-        )
-        .into());
+        return Err(e);
     }
 
     // Read as bytes and decode (UTF-8 should work now, Latin-1 fallback just in case)
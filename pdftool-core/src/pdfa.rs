@@ -0,0 +1,159 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::compress::run_gs_to_pdf;
+use crate::tools::{ICC_PROFILE_ENV, icc_profile};
+use crate::{PdfToolError, Tool, ToolRunner, page_count};
+
+/// PDF/A conformance level written by `to_pdfa`. Only the "b" (basic,
+/// visual appearance) levels are supported, as Ghostscript cannot add the
+/// tagging the "a" levels require.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfaLevel {
+    /// PDF/A-1b (ISO 19005-1), based on PDF 1.4: no transparency, no layers
+    A1b,
+    /// PDF/A-2b (ISO 19005-2), based on PDF 1.7
+    A2b,
+    /// PDF/A-3b (ISO 19005-3): PDF/A-2b that may carry attachments of any type
+    A3b,
+}
+
+impl PdfaLevel {
+    /// Parse "1b", "2b" or "3b" (a "pdfa-" or "a" prefix is accepted).
+    pub fn parse(level: &str) -> Result<Self, PdfToolError> {
+        let lower = level.to_lowercase();
+        let short = lower
+            .trim_start_matches("pdf/a-")
+            .trim_start_matches("pdfa-")
+            .trim_start_matches('a');
+        match short {
+            "1b" | "1" => Ok(PdfaLevel::A1b),
+            "2b" | "2" => Ok(PdfaLevel::A2b),
+            "3b" | "3" => Ok(PdfaLevel::A3b),
+            _ => Err(PdfToolError::InvalidArgument(format!(
+                "Unknown PDF/A level '{}'. Must be one of: 1b, 2b, 3b",
+                level
+            ))),
+        }
+    }
+
+    /// The part of ISO 19005, passed to Ghostscript as `-dPDFA`.
+    fn part(self) -> u8 {
+        match self {
+            PdfaLevel::A1b => 1,
+            PdfaLevel::A2b => 2,
+            PdfaLevel::A3b => 3,
+        }
+    }
+}
+
+impl fmt::Display for PdfaLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PDF/A-{}b", self.part())
+    }
+}
+
+/// Write an archival PDF/A copy of `input`. Colours are converted to sRGB and the
+/// sRGB ICC profile shipped with Ghostscript is embedded as the output intent
+/// (see `PDFTOOL_ICC_PROFILE` to use another one).
+///
+/// Features PDF/A forbids (transparency in PDF/A-1, JavaScript, encryption...) are
+/// dropped rather than failing the conversion.
+///
+/// The PDF version follows from the level, so the runner's version is not used.
+pub fn to_pdfa(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    level: PdfaLevel,
+) -> Result<(), PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    let gs = runner.locate(Tool::Ghostscript);
+    let icc = icc_profile(gs.as_ref().map(|located| located.path.as_path())).ok_or_else(|| {
+        PdfToolError::ToolNotFound {
+            tool: "sRGB ICC profile".to_string(),
+            hint: Some(format!(
+                "Set {} to an sRGB .icc file (Ghostscript ships one as iccprofiles/srgb.icc).",
+                ICC_PROFILE_ENV
+            )),
+        }
+    })?;
+
+    let total = page_count(input).unwrap_or(0);
+    run_gs_to_pdf(
+        runner,
+        input,
+        output,
+        pdfa_args(level, &icc),
+        Some(output_intent(&icc)),
+        total,
+    )
+}
+
+fn pdfa_args(level: PdfaLevel, icc: &Path) -> Vec<String> {
+    vec![
+        "-sDEVICE=pdfwrite".to_string(),
+        format!("-dPDFA={}", level.part()),
+        // Drop what cannot be made conformant instead of writing a non-PDF/A file.
+        "-dPDFACompatibilityPolicy=1".to_string(),
+        "-sColorConversionStrategy=RGB".to_string(),
+        // Keep the output intent defined by the setup code for the whole job.
+        "-dNOOUTERSAVE".to_string(),
+        "-dNOPAUSE".to_string(),
+        "-dBATCH".to_string(),
+        "-dSAFER".to_string(),
+        format!("--permit-file-read={}", icc.display()),
+    ]
+}
+
+/// PostScript that embeds `icc` as the document's output intent; the same as
+/// Ghostscript's lib/PDFA_def.ps with the profile path filled in.
+fn output_intent(icc: &Path) -> String {
+    // PostScript strings treat backslashes as escapes; Ghostscript accepts "/" on Windows too.
+    let path = icc
+        .display()
+        .to_string()
+        .replace('\\', "/")
+        .replace('(', "\\(")
+        .replace(')', "\\)");
+    format!(
+        "[/_objdef {{icc_PDFA}} /type /stream /OBJ pdfmark \
+         [{{icc_PDFA}} << /N 3 >> /PUT pdfmark \
+         [{{icc_PDFA}} ({}) (r) file /PUT pdfmark \
+         [/_objdef {{OutputIntent_PDFA}} /type /dict /OBJ pdfmark \
+         [{{OutputIntent_PDFA}} << /Type /OutputIntent /S /GTS_PDFA1 \
+         /DestOutputProfile {{icc_PDFA}} /OutputConditionIdentifier (sRGB) >> /PUT pdfmark \
+         [{{Catalog}} << /OutputIntents [ {{OutputIntent_PDFA}} ] >> /PUT pdfmark",
+        path
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(PdfaLevel::parse("2b").unwrap(), PdfaLevel::A2b);
+        assert_eq!(PdfaLevel::parse("PDF/A-1b").unwrap(), PdfaLevel::A1b);
+        assert_eq!(PdfaLevel::parse("a3b").unwrap().to_string(), "PDF/A-3b");
+        assert!(PdfaLevel::parse("2a").is_err());
+    }
+
+    #[test]
+    fn test_output_intent_uses_profile() {
+        let icc = Path::new(r"C:\Program Files\PDFTool\ghostscript\iccprofiles\srgb.icc");
+        let args = pdfa_args(PdfaLevel::A2b, icc);
+        assert!(args.contains(&"-dPDFA=2".to_string()));
+        assert!(args.contains(&format!("--permit-file-read={}", icc.display())));
+
+        let setup = output_intent(icc);
+        assert!(
+            setup.contains("(C:/Program Files/PDFTool/ghostscript/iccprofiles/srgb.icc) (r) file")
+        );
+        assert!(setup.contains("/OutputIntents [ {OutputIntent_PDFA} ]"));
+    }
+}
@@ -0,0 +1,413 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    PdfToolError, ReorderMode, ToolRunner, compress_pdf, convert_pdf, extract_pages, extract_text,
+    page_count, reorder_pages, resolve_page_range, resolve_page_sequence, rotate_pages,
+};
+
+/// A sequence of operations applied one after the other, each step reading the
+/// previous step's output. Recipes are usually loaded from TOML or YAML:
+///
+/// ```toml
+/// name = "Scanned invoices"
+///
+/// [[steps]]
+/// op = "extract"
+/// pages = "1-3"
+///
+/// [[steps]]
+/// op = "rotate"
+/// degrees = 90
+///
+/// [[steps]]
+/// op = "compress"
+/// quality = "ebook"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub steps: Vec<Step>,
+}
+
+/// One operation of a pipeline. Page ranges are resolved against the document
+/// as it is at that step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Step {
+    /// Keep only these pages
+    Extract { pages: String },
+    /// Rotate pages clockwise (all pages when `pages` is not given)
+    Rotate {
+        degrees: u32,
+        #[serde(default)]
+        pages: Option<String>,
+    },
+    /// Put pages in this order; pages may repeat
+    Reorder { order: String },
+    /// Reverse the page order
+    Reverse,
+    /// Remove these pages
+    Delete { pages: String },
+    Compress {
+        #[serde(default = "default_quality")]
+        quality: String,
+    },
+    /// Render pages to images. Must be the last step; the output is a directory.
+    Convert {
+        #[serde(default = "default_format")]
+        format: String,
+        #[serde(default = "default_dpi")]
+        dpi: u32,
+        #[serde(default)]
+        pages: Option<String>,
+    },
+    /// Extract the text. Must be the last step; the output is a text file.
+    Text,
+}
+
+fn default_quality() -> String {
+    "ebook".to_string()
+}
+
+fn default_format() -> String {
+    "png".to_string()
+}
+
+fn default_dpi() -> u32 {
+    300
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Extract { pages } => write!(f, "extract {}", pages),
+            Step::Rotate {
+                degrees,
+                pages: None,
+            } => write!(f, "rotate {}°", degrees),
+            Step::Rotate {
+                degrees,
+                pages: Some(pages),
+            } => write!(f, "rotate {} by {}°", pages, degrees),
+            Step::Reorder { order } => write!(f, "reorder {}", order),
+            Step::Reverse => write!(f, "reverse"),
+            Step::Delete { pages } => write!(f, "delete {}", pages),
+            Step::Compress { quality } => write!(f, "compress ({})", quality),
+            Step::Convert { format, dpi, .. } => write!(f, "convert to {} at {} DPI", format, dpi),
+            Step::Text => write!(f, "extract text"),
+        }
+    }
+}
+
+impl Step {
+    /// Steps whose output is not a PDF, so nothing can follow them.
+    fn is_final(&self) -> bool {
+        matches!(self, Step::Convert { .. } | Step::Text)
+    }
+}
+
+impl Pipeline {
+    pub fn from_toml(text: &str) -> Result<Self, PdfToolError> {
+        let pipeline: Pipeline = toml::from_str(text)
+            .map_err(|e| PdfToolError::InvalidArgument(format!("Invalid recipe: {}", e)))?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self, PdfToolError> {
+        let pipeline: Pipeline = serde_yaml::from_str(text)
+            .map_err(|e| PdfToolError::InvalidArgument(format!("Invalid recipe: {}", e)))?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    /// Read a recipe file; `.toml`, `.yaml` and `.yml` are recognised.
+    pub fn load(path: &Path) -> Result<Self, PdfToolError> {
+        if !path.exists() {
+            return Err(PdfToolError::InputNotFound(path.to_path_buf()));
+        }
+        let text = fs::read_to_string(path)?;
+        let ext = path
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_lowercase();
+        let result = match ext.as_str() {
+            "toml" => Pipeline::from_toml(&text),
+            "yaml" | "yml" => Pipeline::from_yaml(&text),
+            _ => {
+                return Err(PdfToolError::InvalidArgument(format!(
+                    "Unknown recipe format '{}'. Use .toml, .yaml or .yml",
+                    path.display()
+                )));
+            }
+        };
+        result.map_err(|e| match e {
+            PdfToolError::InvalidArgument(msg) => {
+                PdfToolError::InvalidArgument(format!("{}: {}", path.display(), msg))
+            }
+            other => other,
+        })
+    }
+
+    fn validate(&self) -> Result<(), PdfToolError> {
+        if self.steps.is_empty() {
+            return Err(PdfToolError::InvalidArgument(
+                "A pipeline needs at least one step".to_string(),
+            ));
+        }
+        if let Some(step) = self.steps[..self.steps.len() - 1]
+            .iter()
+            .find(|s| s.is_final())
+        {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "'{}' must be the last step of a pipeline",
+                step
+            )));
+        }
+        Ok(())
+    }
+
+    /// Extension of the file the pipeline writes: "pdf", or "txt" when it ends by
+    /// extracting the text. None when it ends with `convert`, which writes images
+    /// into a directory.
+    pub fn output_extension(&self) -> Option<&'static str> {
+        match self.steps.last() {
+            Some(Step::Convert { .. }) => None,
+            Some(Step::Text) => Some("txt"),
+            _ => Some("pdf"),
+        }
+    }
+
+    /// Run every step on `input`. `output` is the final file, or the directory
+    /// for the images when the last step is `convert`. Intermediate results are
+    /// kept in a temporary directory that is removed afterwards, whatever happens.
+    ///
+    /// A runner PDF version applies to the final file only.
+    pub fn run(
+        &self,
+        runner: &ToolRunner,
+        input: &Path,
+        output: &Path,
+    ) -> Result<(), PdfToolError> {
+        self.validate()?;
+        if !input.exists() {
+            return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+        }
+
+        let scratch = ScratchDir::new()?;
+        // Intermediate files keep the input's name, so a final `convert` names
+        // its images after the original document.
+        let file_name = Path::new(input.file_stem().unwrap_or_default()).with_extension("pdf");
+        let mut current = input.to_path_buf();
+        // Every feature of the result comes from the input, so it is checked once.
+        runner.check_version_loss(input);
+        let last_runner = runner.without_warnings();
+        let step_runner = last_runner.without_pdf_version();
+
+        for (i, step) in self.steps.iter().enumerate() {
+            runner.check_cancelled()?;
+            let last = i + 1 == self.steps.len();
+            let target = if last {
+                output.to_path_buf()
+            } else {
+                let dir = scratch.path.join(format!("step{}", i + 1));
+                fs::create_dir_all(&dir)?;
+                dir.join(&file_name)
+            };
+            run_step(
+                if last { &last_runner } else { &step_runner },
+                step,
+                &current,
+                &target,
+            )?;
+            current = target;
+        }
+        Ok(())
+    }
+}
+
+fn run_step(
+    runner: &ToolRunner,
+    step: &Step,
+    input: &Path,
+    output: &Path,
+) -> Result<(), PdfToolError> {
+    match step {
+        Step::Extract { pages } => {
+            let pages = resolve_page_range(pages, page_count(input)?)?;
+            extract_pages(runner, input, output, &pages)
+        }
+        Step::Rotate { degrees, pages } => {
+            let pages = match pages {
+                Some(p) => Some(resolve_page_range(p, page_count(input)?)?),
+                None => None,
+            };
+            rotate_pages(runner, input, output, pages.as_deref(), *degrees)
+        }
+        Step::Reorder { order } => {
+            let order = resolve_page_sequence(order, page_count(input)?)?;
+            reorder_pages(runner, input, output, &ReorderMode::Sequence(order))
+        }
+        Step::Reverse => reorder_pages(runner, input, output, &ReorderMode::Reverse),
+        Step::Delete { pages } => {
+            let pages = resolve_page_range(pages, page_count(input)?)?;
+            reorder_pages(runner, input, output, &ReorderMode::Delete(pages))
+        }
+        Step::Compress { quality } => compress_pdf(runner, input, output, quality).map(|_| ()),
+        Step::Convert { format, dpi, pages } => {
+            let pages = match pages {
+                Some(p) => Some(resolve_page_range(p, page_count(input)?)?),
+                None => None,
+            };
+            convert_pdf(runner, input, output, format, *dpi, pages.as_deref())
+        }
+        Step::Text => extract_text(runner, input, output),
+    }
+}
+
+/// A fresh directory under the system temp directory, deleted on drop.
+struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    fn new() -> Result<Self, PdfToolError> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "pdftool-pipeline-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        let path = std::env::temp_dir().join(name);
+        fs::create_dir_all(&path)?;
+        Ok(ScratchDir { path })
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tool;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    const RECIPE: &str = r#"
+name = "Invoices"
+
+[[steps]]
+op = "extract"
+pages = "2-end"
+
+[[steps]]
+op = "reverse"
+
+[[steps]]
+op = "compress"
+"#;
+
+    #[test]
+    fn test_toml_and_yaml_recipes() {
+        let toml = Pipeline::from_toml(RECIPE).unwrap();
+        assert_eq!(toml.name.as_deref(), Some("Invoices"));
+        assert_eq!(
+            toml.steps,
+            [
+                Step::Extract {
+                    pages: "2-end".to_string()
+                },
+                Step::Reverse,
+                Step::Compress {
+                    quality: "ebook".to_string()
+                },
+            ]
+        );
+
+        let yaml = "name: Invoices\nsteps:\n  - op: extract\n    pages: 2-end\n  - op: reverse\n  - op: compress\n";
+        assert_eq!(Pipeline::from_yaml(yaml).unwrap(), toml);
+        assert_eq!(toml.output_extension(), Some("pdf"));
+    }
+
+    #[test]
+    fn test_invalid_recipes() {
+        let unknown = "[[steps]]\nop = \"shred\"\n";
+        assert!(matches!(
+            Pipeline::from_toml(unknown),
+            Err(PdfToolError::InvalidArgument(_))
+        ));
+        let typo = "[[steps]]\nop = \"compress\"\nqualty = \"screen\"\n";
+        assert!(Pipeline::from_toml(typo).is_err());
+        assert!(Pipeline::from_toml("steps = []").is_err());
+
+        let text_first = "[[steps]]\nop = \"text\"\n[[steps]]\nop = \"reverse\"\n";
+        let err = Pipeline::from_toml(text_first).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "'extract text' must be the last step of a pipeline"
+        );
+    }
+
+    #[test]
+    fn test_steps_chain_through_temp_files() {
+        let input = temp_path("pipeline_in.pdf");
+        let output = temp_path("pipeline_out.pdf");
+        sample_pdf(&input, 4);
+        let fake = FakeBackend::succeeding();
+
+        Pipeline::from_toml(RECIPE)
+            .unwrap()
+            .run(&ToolRunner::new(fake.clone()), &input, &output)
+            .unwrap();
+
+        // extract (Ghostscript) → reverse (lopdf) → compress (Ghostscript)
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 2);
+        assert!(calls.iter().all(|(tool, _)| *tool == Tool::Ghostscript));
+        let compress_input = calls[1].1.last().unwrap();
+        assert!(compress_input.ends_with("pipeline_in.pdf"));
+        assert_ne!(Path::new(compress_input), input);
+        // The temporary files are gone
+        assert!(!Path::new(compress_input).exists());
+        // The fake Ghostscript copies its input, so all four pages are still there.
+        assert_eq!(page_count(&output).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_pdf_version_applies_to_final_file() {
+        let input = temp_path("pipeline_version_in.pdf");
+        let output = temp_path("pipeline_version_out.pdf");
+        sample_pdf(&input, 2);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone())
+            .with_pdf_version(crate::PdfVersion::parse("1.4").unwrap());
+
+        let recipe = "[[steps]]\nop = \"compress\"\n[[steps]]\nop = \"reverse\"\n";
+        Pipeline::from_toml(recipe)
+            .unwrap()
+            .run(&runner, &input, &output)
+            .unwrap();
+
+        // compress to an intermediate file at any version, then the reversed file rewritten as PDF 1.4
+        let calls = fake.calls();
+        assert_eq!(calls.len(), 2);
+        let version = "-dCompatibilityLevel=1.4".to_string();
+        assert!(!calls[0].1.contains(&version));
+        assert!(calls[1].1.contains(&version));
+        assert_eq!(page_count(&output).unwrap(), 2);
+    }
+}
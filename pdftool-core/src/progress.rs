@@ -0,0 +1,43 @@
+use serde::Serialize;
+
+/// How far a long operation has got, counted in pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub done: u32,
+    pub total: u32,
+}
+
+impl Progress {
+    /// Completed share, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done.min(self.total) as f64) / (self.total as f64)
+    }
+}
+
+/// Ghostscript prints "Page N" on stdout when it starts each page (unless run with -q).
+pub(crate) fn is_gs_page_line(line: &str) -> bool {
+    line.trim()
+        .strip_prefix("Page ")
+        .is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gs_page_line() {
+        assert!(is_gs_page_line("Page 12\n"));
+        assert!(!is_gs_page_line("Processing pages 1 through 12."));
+        assert!(!is_gs_page_line("Page count: 3"));
+    }
+
+    #[test]
+    fn test_fraction() {
+        assert_eq!(Progress { done: 1, total: 4 }.fraction(), 0.25);
+        assert_eq!(Progress { done: 0, total: 0 }.fraction(), 0.0);
+    }
+}
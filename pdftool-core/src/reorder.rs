@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use lopdf::{Document, Object, ObjectId};
+
+use crate::version::apply_pdf_version;
+use crate::{PdfToolError, ToolRunner, inherited_attribute, validate_pages};
+
+/// Attributes a page can inherit from its ancestors in the page tree.
+const INHERITABLE: &[&[u8]] = &[b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// How to rearrange the pages of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReorderMode {
+    /// Output exactly these pages, in this order (repeats allowed)
+    Sequence(Vec<u32>),
+    /// Output all pages, last to first
+    Reverse,
+    /// Output all pages except these
+    Delete(Vec<u32>),
+}
+
+/// Write a copy of `input` whose pages are rearranged according to `mode`.
+///
+/// The output keeps the input's PDF version unless the runner has one.
+pub fn reorder_pages(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    mode: &ReorderMode,
+) -> Result<(), PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    let mut doc = Document::load(input).map_err(|e| {
+        PdfToolError::InvalidPdf(format!("Could not read PDF {}: {}", input.display(), e))
+    })?;
+    let page_ids = doc.get_pages();
+    let total = page_ids.len() as u32;
+
+    let sequence = page_sequence(mode, total)?;
+    if sequence.is_empty() {
+        return Err(PdfToolError::InvalidArgument(
+            "The output would have no pages".to_string(),
+        ));
+    }
+
+    let root_pages = doc
+        .catalog()?
+        .get(b"Pages")
+        .and_then(Object::as_reference)?;
+
+    // Pages are re-attached directly under the root, so copy down anything they
+    // inherited from intermediate nodes first.
+    for id in page_ids.values() {
+        let inherited: Vec<(&[u8], Object)> = INHERITABLE
+            .iter()
+            .filter(|key| !doc.get_dictionary(*id).is_ok_and(|d| d.has(key)))
+            .filter_map(|key| inherited_attribute(&doc, *id, key).map(|v| (*key, v.clone())))
+            .collect();
+        let page = doc.get_dictionary_mut(*id)?;
+        for (key, value) in inherited {
+            page.set(key, value);
+        }
+        page.set("Parent", root_pages);
+    }
+
+    let mut used = HashSet::new();
+    let mut kids: Vec<Object> = Vec::new();
+    for page in &sequence {
+        let id = page_ids[page];
+        if used.insert(id) {
+            kids.push(id.into());
+        } else {
+            // A page object can only appear once in the tree; repeats get a copy.
+            let copy = doc.get_dictionary(id)?.clone();
+            let copy_id: ObjectId = doc.add_object(copy);
+            kids.push(copy_id.into());
+        }
+    }
+
+    let root = doc.get_dictionary_mut(root_pages)?;
+    root.set("Count", kids.len() as i64);
+    root.set("Kids", kids);
+
+    doc.prune_objects();
+    doc.save(output)?;
+
+    apply_pdf_version(runner, output)
+}
+
+/// Resolve a reorder mode into the list of source pages to output, in order.
+fn page_sequence(mode: &ReorderMode, total: u32) -> Result<Vec<u32>, PdfToolError> {
+    match mode {
+        ReorderMode::Sequence(pages) => {
+            validate_pages(pages, total)?;
+            Ok(pages.clone())
+        }
+        ReorderMode::Reverse => Ok((1..=total).rev().collect()),
+        ReorderMode::Delete(pages) => {
+            validate_pages(pages, total)?;
+            Ok((1..=total).filter(|p| !pages.contains(p)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    /// Read back the page numbers written by `sample_pdf`, in document order.
+    fn page_labels(path: &Path) -> Vec<u32> {
+        let doc = Document::load(path).unwrap();
+        doc.get_pages()
+            .values()
+            .map(|id| {
+                let content = doc.get_page_content(*id).unwrap();
+                let text = String::from_utf8(content).unwrap();
+                let start = text.find("(Page ").unwrap() + 6;
+                let end = start + text[start..].find(')').unwrap();
+                text[start..end].parse().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sequence_with_repeats() {
+        let input = temp_path("reorder_seq_in.pdf");
+        let output = temp_path("reorder_seq_out.pdf");
+        sample_pdf(&input, 5);
+
+        reorder_pages(
+            &ToolRunner::default(),
+            &input,
+            &output,
+            &ReorderMode::Sequence(vec![5, 1, 2, 3, 4, 5]),
+        )
+        .unwrap();
+        assert_eq!(page_labels(&output), vec![5, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let input = temp_path("reorder_rev_in.pdf");
+        let output = temp_path("reorder_rev_out.pdf");
+        sample_pdf(&input, 3);
+
+        reorder_pages(
+            &ToolRunner::default(),
+            &input,
+            &output,
+            &ReorderMode::Reverse,
+        )
+        .unwrap();
+        assert_eq!(page_labels(&output), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_delete() {
+        let input = temp_path("reorder_del_in.pdf");
+        let output = temp_path("reorder_del_out.pdf");
+        sample_pdf(&input, 4);
+
+        reorder_pages(
+            &ToolRunner::default(),
+            &input,
+            &output,
+            &ReorderMode::Delete(vec![2, 4]),
+        )
+        .unwrap();
+        assert_eq!(page_labels(&output), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_delete_all_pages_error() {
+        assert!(
+            page_sequence(&ReorderMode::Delete(vec![1, 2]), 2)
+                .unwrap()
+                .is_empty()
+        );
+        let input = temp_path("reorder_delall_in.pdf");
+        sample_pdf(&input, 2);
+        let mode = ReorderMode::Delete(vec![1, 2]);
+        assert!(
+            reorder_pages(
+                &ToolRunner::default(),
+                &input,
+                &temp_path("reorder_delall_out.pdf"),
+                &mode
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_missing_page_error() {
+        assert!(page_sequence(&ReorderMode::Sequence(vec![1, 9]), 3).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use lopdf::{Document, ObjectId};
+
+use crate::version::apply_pdf_version;
+use crate::{PdfToolError, ToolRunner, inherited_attribute, validate_pages};
+
+const VALID_ROTATIONS: &[u32] = &[90, 180, 270];
+
+/// Rotate pages clockwise by 90, 180 or 270 degrees.
+/// `pages` selects which pages to turn (None = every page); the others are left untouched.
+/// The rotation is added to any rotation the page already has.
+///
+/// The output keeps the input's PDF version unless the runner has one.
+pub fn rotate_pages(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    pages: Option<&[u32]>,
+    degrees: u32,
+) -> Result<(), PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    if !VALID_ROTATIONS.contains(&degrees) {
+        return Err(PdfToolError::InvalidArgument(format!(
+            "Invalid rotation '{}'. Must be one of: 90, 180, 270",
+            degrees
+        )));
+    }
+
+    let mut doc = Document::load(input).map_err(|e| {
+        PdfToolError::InvalidPdf(format!("Could not read PDF {}: {}", input.display(), e))
+    })?;
+    let page_ids = doc.get_pages();
+
+    let selected: Vec<ObjectId> = match pages {
+        Some(pages) => {
+            validate_pages(pages, page_ids.len() as u32)?;
+            pages.iter().map(|page| page_ids[page]).collect()
+        }
+        None => page_ids.values().copied().collect(),
+    };
+
+    for id in selected {
+        let current = page_rotation(&doc, id);
+        let rotation = (current + degrees as i64).rem_euclid(360);
+        doc.get_dictionary_mut(id)?.set("Rotate", rotation);
+    }
+
+    doc.save(output)?;
+
+    apply_pdf_version(runner, output)
+}
+
+/// Read the /Rotate of a page, following the page tree since the value is inheritable.
+pub(crate) fn page_rotation(doc: &Document, page_id: ObjectId) -> i64 {
+    inherited_attribute(doc, page_id, b"Rotate")
+        .and_then(|rotate| rotate.as_i64().ok())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    fn rotations(path: &Path) -> Vec<i64> {
+        let doc = Document::load(path).unwrap();
+        doc.get_pages()
+            .values()
+            .map(|id| page_rotation(&doc, *id))
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_selected_pages() {
+        let input = temp_path("rotate_selected_in.pdf");
+        let output = temp_path("rotate_selected_out.pdf");
+        sample_pdf(&input, 4);
+
+        rotate_pages(&ToolRunner::default(), &input, &output, Some(&[2, 4]), 90).unwrap();
+        assert_eq!(rotations(&output), vec![0, 90, 0, 90]);
+
+        rotate_pages(&ToolRunner::default(), &output, &output, Some(&[2]), 270).unwrap();
+        assert_eq!(rotations(&output), vec![0, 0, 0, 90]);
+    }
+
+    #[test]
+    fn test_rotate_all_pages() {
+        let input = temp_path("rotate_all_in.pdf");
+        let output = temp_path("rotate_all_out.pdf");
+        sample_pdf(&input, 3);
+
+        rotate_pages(&ToolRunner::default(), &input, &output, None, 180).unwrap();
+        assert_eq!(rotations(&output), vec![180, 180, 180]);
+    }
+
+    #[test]
+    fn test_rotate_at_runner_pdf_version() {
+        let input = temp_path("rotate_version_in.pdf");
+        let output = temp_path("rotate_version_out.pdf");
+        sample_pdf(&input, 2);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone())
+            .with_pdf_version(crate::PdfVersion::parse("1.3").unwrap());
+
+        rotate_pages(&runner, &input, &output, None, 90).unwrap();
+        assert!(
+            fake.calls()[0]
+                .1
+                .contains(&"-dCompatibilityLevel=1.3".to_string())
+        );
+        assert_eq!(rotations(&output), vec![90, 90]);
+    }
+
+    #[test]
+    fn test_invalid_rotation_error() {
+        let input = temp_path("rotate_invalid_in.pdf");
+        sample_pdf(&input, 1);
+        assert!(
+            rotate_pages(
+                &ToolRunner::default(),
+                &input,
+                &temp_path("rotate_invalid_out.pdf"),
+                None,
+                45
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_missing_page_error() {
+        let input = temp_path("rotate_missing_in.pdf");
+        sample_pdf(&input, 2);
+        let err = rotate_pages(
+            &ToolRunner::default(),
+            &input,
+            &temp_path("rotate_missing_out.pdf"),
+            Some(&[3]),
+            90,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("valid: 1-2"), "{}", err);
+    }
+}
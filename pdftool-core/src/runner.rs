@@ -0,0 +1,613 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::progress::is_gs_page_line;
+use crate::tools::{gs_lib_env, gs_lib_path};
+use crate::{CancelToken, Located, PdfToolError, PdfVersion, Progress, Tool, version_warnings};
+
+/// How often a running process is checked for completion or timeout.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// What an external program printed, and how it exited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolOutput {
+    /// Exit code, or None if the process was killed by a signal
+    pub code: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl ToolOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Called with each line a tool writes to stdout, while it runs.
+pub type LineHook = dyn Fn(&str) + Send + Sync;
+
+/// One run of an external program, as handed to a `Backend`.
+pub struct Invocation<'a> {
+    pub tool: Tool,
+    /// The executable located for `tool`
+    pub program: &'a Path,
+    pub args: &'a [String],
+    /// Kill the program if it runs longer than this
+    pub timeout: Option<Duration>,
+    /// Kill the program when this is cancelled
+    pub cancel: Option<&'a CancelToken>,
+    pub on_stdout_line: Option<Arc<LineHook>>,
+}
+
+/// Starts external programs. `SystemBackend` runs the real executables;
+/// tests substitute a fake that records invocations.
+pub trait Backend: Send + Sync {
+    /// The executable that would run for `tool`, if there is one.
+    fn locate(&self, tool: Tool) -> Option<Located>;
+
+    /// Run the program until it exits. A non-zero exit is not an error at this level.
+    fn execute(&self, invocation: &Invocation) -> Result<ToolOutput, PdfToolError>;
+}
+
+/// Runs the executables found by `Tool::locate`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemBackend;
+
+impl Backend for SystemBackend {
+    fn locate(&self, tool: Tool) -> Option<Located> {
+        tool.locate()
+    }
+
+    fn execute(&self, invocation: &Invocation) -> Result<ToolOutput, PdfToolError> {
+        let mut cmd = Command::new(invocation.program);
+        cmd.args(invocation.args);
+
+        // Set GS library search path if bundled
+        if invocation.tool == Tool::Ghostscript
+            && let Some(search_path) = gs_lib_path().and_then(|dir| gs_lib_env(&dir))
+        {
+            cmd.env("GS_LIB", search_path);
+        }
+
+        run_process(
+            invocation.tool,
+            &mut cmd,
+            invocation.timeout,
+            invocation.cancel,
+            invocation.on_stdout_line.clone(),
+        )
+    }
+}
+
+/// Spawn `cmd` with its output captured and wait for it, killing it after `timeout`
+/// or when `cancel` is triggered.
+fn run_process(
+    tool: Tool,
+    cmd: &mut Command,
+    timeout: Option<Duration>,
+    cancel: Option<&CancelToken>,
+    on_stdout_line: Option<Arc<LineHook>>,
+) -> Result<ToolOutput, PdfToolError> {
+    let cancelled = || cancel.is_some_and(CancelToken::is_cancelled);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| PdfToolError::spawn(tool.name(), e))?;
+
+    // Drain both pipes while waiting so the child never blocks on a full pipe.
+    let stdout = read_in_background(child.stdout.take(), on_stdout_line);
+    let stderr = read_in_background(child.stderr.take(), None);
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(PdfToolError::Cancelled);
+        }
+        if let Some(limit) = timeout
+            && started.elapsed() >= limit
+        {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(PdfToolError::Timeout {
+                tool: tool.name().to_string(),
+                after: limit,
+            });
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // Ctrl-C in a terminal also reaches the child, which may exit on its own first.
+    if cancelled() {
+        return Err(PdfToolError::Cancelled);
+    }
+
+    Ok(ToolOutput {
+        code: status.code(),
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Read a pipe to the end on another thread, passing each line to `on_line` as it arrives.
+fn read_in_background<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    on_line: Option<Arc<LineHook>>,
+) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let Some(pipe) = pipe else {
+            return buffer;
+        };
+        let mut reader = BufReader::new(pipe);
+        loop {
+            let start = buffer.len();
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if let Some(on_line) = &on_line {
+                        on_line(&String::from_utf8_lossy(&buffer[start..]));
+                    }
+                }
+            }
+        }
+        buffer
+    })
+}
+
+/// Runs external tools for the operations. Every invocation goes through here so
+/// discovery, environment, timeouts and error mapping behave the same everywhere.
+#[derive(Clone)]
+pub struct ToolRunner {
+    backend: Arc<dyn Backend>,
+    timeout: Option<Duration>,
+    cancel: Option<CancelToken>,
+    on_launch: Option<Arc<LaunchHook>>,
+    on_progress: Option<Arc<ProgressHook>>,
+    on_warning: Option<Arc<WarningHook>>,
+    pdf_version: Option<PdfVersion>,
+}
+
+type LaunchHook = dyn Fn(&Located) + Send + Sync;
+type ProgressHook = dyn Fn(Progress) + Send + Sync;
+type WarningHook = dyn Fn(&str) + Send + Sync;
+
+impl ToolRunner {
+    pub fn new(backend: Arc<dyn Backend>) -> Self {
+        ToolRunner {
+            backend,
+            timeout: None,
+            cancel: None,
+            on_launch: None,
+            on_progress: None,
+            on_warning: None,
+            pdf_version: None,
+        }
+    }
+
+    /// A runner for the real executables, without a timeout.
+    pub fn system() -> Self {
+        ToolRunner::new(Arc::new(SystemBackend))
+    }
+
+    /// Kill any tool that runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stop operations using this runner when `token` is cancelled: the running
+    /// tool is killed and the operation fails with `PdfToolError::Cancelled`.
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Fail with `PdfToolError::Cancelled` if the operation was cancelled.
+    /// Operations made of several steps call this between steps.
+    pub(crate) fn check_cancelled(&self) -> Result<(), PdfToolError> {
+        match &self.cancel {
+            Some(token) if token.is_cancelled() => Err(PdfToolError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Call `hook` with the chosen executable every time a tool is started,
+    /// e.g. to report which binary was used.
+    pub fn on_launch(mut self, hook: impl Fn(&Located) + Send + Sync + 'static) -> Self {
+        self.on_launch = Some(Arc::new(hook));
+        self
+    }
+
+    /// Call `hook` as operations work through their pages.
+    pub fn on_progress(mut self, hook: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(hook));
+        self
+    }
+
+    /// The same runner without its progress hook, for steps of an operation
+    /// that reports progress itself.
+    pub(crate) fn without_progress(&self) -> Self {
+        ToolRunner {
+            on_progress: None,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn report_progress(&self, done: u32, total: u32) {
+        if let Some(hook) = &self.on_progress {
+            hook(Progress { done, total });
+        }
+    }
+
+    /// Call `hook` with things the user should know about an operation that
+    /// still succeeds, such as features lost by writing an older PDF version.
+    pub fn on_warning(mut self, hook: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.on_warning = Some(Arc::new(hook));
+        self
+    }
+
+    /// The same runner without its warning hook, for steps of an operation
+    /// that has already checked its inputs.
+    pub(crate) fn without_warnings(&self) -> Self {
+        ToolRunner {
+            on_warning: None,
+            ..self.clone()
+        }
+    }
+
+    /// Write the PDFs that Ghostscript produces as `version` instead of
+    /// Ghostscript's default (or the preset's, for compression).
+    pub fn with_pdf_version(mut self, version: PdfVersion) -> Self {
+        self.pdf_version = Some(version);
+        self
+    }
+
+    /// The same runner writing Ghostscript's default version, for intermediate
+    /// files whose version does not matter.
+    pub(crate) fn without_pdf_version(&self) -> Self {
+        ToolRunner {
+            pdf_version: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn pdf_version(&self) -> Option<PdfVersion> {
+        self.pdf_version
+    }
+
+    /// Ghostscript arguments selecting pdfwrite, at the runner's PDF version if it has one.
+    pub(crate) fn pdfwrite_args(&self) -> Vec<String> {
+        let mut args = vec!["-sDEVICE=pdfwrite".to_string()];
+        if let Some(version) = self.pdf_version {
+            args.push(format!("-dCompatibilityLevel={}", version));
+        }
+        args
+    }
+
+    /// Warn about the features of `input` that the runner's PDF version cannot keep.
+    pub(crate) fn check_version_loss(&self, input: &Path) {
+        let (Some(version), Some(hook)) = (self.pdf_version, &self.on_warning) else {
+            return;
+        };
+        for warning in version_warnings(input, version).unwrap_or_default() {
+            hook(&format!("{}: {}", input.display(), warning));
+        }
+    }
+
+    /// The executable that would run for `tool`, if there is one.
+    pub fn locate(&self, tool: Tool) -> Option<Located> {
+        self.backend.locate(tool)
+    }
+
+    /// Run `tool` to completion. A non-zero exit becomes `PdfToolError::ToolFailed`
+    /// carrying the end of what the tool printed.
+    pub fn run<S: AsRef<str>>(&self, tool: Tool, args: &[S]) -> Result<ToolOutput, PdfToolError> {
+        check_status(tool, self.execute(tool, args, None)?)
+    }
+
+    /// Run Ghostscript on a job of `total` pages, reporting progress from the
+    /// "Page N" lines it prints. `total` = 0 means the page count is unknown.
+    pub(crate) fn run_gs_pages<S: AsRef<str>>(
+        &self,
+        args: &[S],
+        total: u32,
+    ) -> Result<ToolOutput, PdfToolError> {
+        let Some(hook) = self.on_progress.clone().filter(|_| total > 0) else {
+            return self.run(Tool::Ghostscript, args);
+        };
+
+        hook(Progress { done: 0, total });
+        let done = Arc::new(AtomicU32::new(0));
+        let on_line = {
+            let done = done.clone();
+            let hook = hook.clone();
+            move |line: &str| {
+                if is_gs_page_line(line) {
+                    let done = (done.fetch_add(1, Ordering::SeqCst) + 1).min(total);
+                    hook(Progress { done, total });
+                }
+            }
+        };
+
+        let output = check_status(
+            Tool::Ghostscript,
+            self.execute(Tool::Ghostscript, args, Some(Arc::new(on_line)))?,
+        )?;
+        if done.load(Ordering::SeqCst) < total {
+            hook(Progress { done: total, total });
+        }
+        Ok(output)
+    }
+
+    /// Run `tool` to completion and return what it printed, whatever its exit code.
+    pub fn output<S: AsRef<str>>(
+        &self,
+        tool: Tool,
+        args: &[S],
+    ) -> Result<ToolOutput, PdfToolError> {
+        self.execute(tool, args, None)
+    }
+
+    fn execute<S: AsRef<str>>(
+        &self,
+        tool: Tool,
+        args: &[S],
+        on_stdout_line: Option<Arc<LineHook>>,
+    ) -> Result<ToolOutput, PdfToolError> {
+        self.check_cancelled()?;
+        let located = self
+            .locate(tool)
+            .ok_or_else(|| PdfToolError::ToolNotFound {
+                tool: tool.name().to_string(),
+                hint: None,
+            })?;
+        if let Some(hook) = &self.on_launch {
+            hook(&located);
+        }
+
+        let args: Vec<String> = args.iter().map(|a| a.as_ref().to_string()).collect();
+        self.backend.execute(&Invocation {
+            tool,
+            program: &located.path,
+            args: &args,
+            timeout: self.timeout,
+            cancel: self.cancel.as_ref(),
+            on_stdout_line,
+        })
+    }
+}
+
+/// Turn a non-zero exit into `PdfToolError::ToolFailed`.
+fn check_status(tool: Tool, output: ToolOutput) -> Result<ToolOutput, PdfToolError> {
+    if !output.success() {
+        return Err(PdfToolError::tool_failed(tool.name(), &output));
+    }
+    Ok(output)
+}
+
+impl Default for ToolRunner {
+    fn default() -> Self {
+        ToolRunner::system()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod fake {
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::ToolSource;
+
+    type Respond = dyn Fn(Tool, &[String]) -> ToolOutput + Send + Sync;
+
+    /// A backend that records invocations instead of running anything.
+    pub struct FakeBackend {
+        calls: Mutex<Vec<(Tool, Vec<String>)>>,
+        respond: Box<Respond>,
+    }
+
+    impl FakeBackend {
+        /// Answer every invocation with `respond`.
+        pub fn new(
+            respond: impl Fn(Tool, &[String]) -> ToolOutput + Send + Sync + 'static,
+        ) -> Arc<Self> {
+            Arc::new(FakeBackend {
+                calls: Mutex::new(Vec::new()),
+                respond: Box::new(respond),
+            })
+        }
+
+        /// Every tool succeeds (see `succeed`).
+        pub fn succeeding() -> Arc<Self> {
+            FakeBackend::new(succeed)
+        }
+
+        pub fn calls(&self) -> Vec<(Tool, Vec<String>)> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    /// A successful run. Ghostscript "writes" its -sOutputFile by copying
+    /// the last input file, so callers that read their outputs keep working.
+    pub fn succeed(tool: Tool, args: &[String]) -> ToolOutput {
+        if tool == Tool::Ghostscript
+            && let Some(output) = args.iter().find_map(|a| a.strip_prefix("-sOutputFile="))
+            && let Some(input) = args.last()
+        {
+            std::fs::copy(input, output).unwrap();
+        }
+        ToolOutput {
+            code: Some(0),
+            ..Default::default()
+        }
+    }
+
+    impl Backend for FakeBackend {
+        fn locate(&self, tool: Tool) -> Option<Located> {
+            Some(Located {
+                tool,
+                path: PathBuf::from(tool.name()),
+                source: ToolSource::Path,
+            })
+        }
+
+        fn execute(&self, invocation: &Invocation) -> Result<ToolOutput, PdfToolError> {
+            let (tool, args) = (invocation.tool, invocation.args);
+            self.calls.lock().unwrap().push((tool, args.to_vec()));
+            let output = (self.respond)(tool, args);
+            if let Some(on_line) = &invocation.on_stdout_line {
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    on_line(line);
+                }
+            }
+            Ok(output)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::FakeBackend;
+    use super::*;
+
+    #[test]
+    fn test_failure_becomes_tool_failed() {
+        let fake = FakeBackend::new(|_, _| ToolOutput {
+            code: Some(1),
+            stdout: b"GPL Ghostscript\n".to_vec(),
+            stderr: b"Error: /undefinedfilename\n".to_vec(),
+        });
+        let runner = ToolRunner::new(fake.clone());
+
+        let error = runner.run(Tool::Ghostscript, &["in.pdf"]).unwrap_err();
+        assert_eq!(error.kind(), "tool_failed");
+        assert_eq!(
+            error.to_string(),
+            "Ghostscript exited with code: 1\nError: /undefinedfilename"
+        );
+        assert_eq!(
+            fake.calls(),
+            vec![(Tool::Ghostscript, vec!["in.pdf".to_string()])]
+        );
+    }
+
+    #[test]
+    fn test_launch_hook_reports_executable() {
+        let launched = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = launched.clone();
+        let runner = ToolRunner::new(FakeBackend::succeeding())
+            .on_launch(move |located| seen.lock().unwrap().push(located.to_string()));
+
+        runner.run(Tool::Pdftotext, &["in.pdf"]).unwrap();
+        assert_eq!(
+            *launched.lock().unwrap(),
+            ["pdftotext: pdftotext (found in PATH)"]
+        );
+    }
+
+    #[test]
+    fn test_gs_page_progress() {
+        let fake = FakeBackend::new(|_, _| ToolOutput {
+            code: Some(0),
+            stdout: b"Processing pages 1 through 3.\nPage 1\nPage 2\n".to_vec(),
+            ..Default::default()
+        });
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let runner = ToolRunner::new(fake)
+            .on_progress(move |p| recorded.lock().unwrap().push((p.done, p.total)));
+
+        runner.run_gs_pages(&["in.pdf"], 3).unwrap();
+        // The last page is reported on success even if its line was missed.
+        assert_eq!(*seen.lock().unwrap(), [(0, 3), (1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stdout_lines_are_streamed() {
+        let lines = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = lines.clone();
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo 'Page 1'; echo 'Page 2'"]);
+        let on_line: Arc<LineHook> =
+            Arc::new(move |line: &str| recorded.lock().unwrap().push(line.to_string()));
+        let output = run_process(Tool::Ghostscript, &mut cmd, None, None, Some(on_line)).unwrap();
+        assert_eq!(*lines.lock().unwrap(), ["Page 1\n", "Page 2\n"]);
+        assert_eq!(output.stdout, b"Page 1\nPage 2\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_process_output_is_captured() {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo out; echo err >&2; exit 3"]);
+        let output = run_process(Tool::Ghostscript, &mut cmd, None, None, None).unwrap();
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_process() {
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        let started = Instant::now();
+        let error = run_process(
+            Tool::Ghostscript,
+            &mut cmd,
+            Some(Duration::from_millis(100)),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), "timeout");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_kills_process() {
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+        let mut cmd = Command::new("sleep");
+        cmd.arg("5");
+        let started = Instant::now();
+        let error = run_process(Tool::Ghostscript, &mut cmd, None, Some(&token), None).unwrap_err();
+        assert_eq!(error.kind(), "cancelled");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_cancelled_runner_starts_nothing() {
+        let fake = FakeBackend::succeeding();
+        let token = CancelToken::new();
+        token.cancel();
+        let runner = ToolRunner::new(fake.clone()).with_cancel(token);
+        assert_eq!(
+            runner
+                .run(Tool::Ghostscript, &["in.pdf"])
+                .unwrap_err()
+                .kind(),
+            "cancelled"
+        );
+        assert!(fake.calls().is_empty());
+    }
+}
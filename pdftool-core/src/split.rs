@@ -0,0 +1,364 @@
+use std::path::{Path, PathBuf};
+
+use crate::{PdfToolError, ToolRunner, extract_pages, page_count, validate_pages};
+
+/// Default output name template for `split_pdf`.
+pub const DEFAULT_SPLIT_TEMPLATE: &str = "{name}_part{num}";
+
+/// How to cut a document into parts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SplitMode {
+    /// A new part every N pages
+    EveryN(u32),
+    /// A new part starts at each of these pages
+    AtPages(Vec<u32>),
+    /// Parts as large as possible without exceeding this many bytes
+    MaxBytes(u64),
+}
+
+/// Split a PDF into numbered files written to `output_dir`.
+///
+/// `name_template` controls the file names (".pdf" is appended) and understands
+/// `{name}` (input file stem), `{num}` (part number, zero-padded), `{start}` and `{end}`
+/// (first and last page of the part). When there is more than one part, the template
+/// must contain `{num}`, `{start}` or `{end}` so that every part gets its own file.
+///
+/// With `SplitMode::MaxBytes`, every page is first extracted on its own to measure it,
+/// and pages are then grouped greedily. A single page larger than the limit still
+/// gets its own part.
+///
+/// Returns the paths of the files written, in page order.
+pub fn split_pdf(
+    runner: &ToolRunner,
+    input: &Path,
+    output_dir: &Path,
+    mode: &SplitMode,
+    name_template: &str,
+) -> Result<Vec<PathBuf>, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+
+    let total = page_count(input)?;
+    let stem = input
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    std::fs::create_dir_all(output_dir)?;
+
+    // Progress counts pages written; size-based splits also count the pages
+    // measured beforehand, so they go over the document twice.
+    let steps = match mode {
+        SplitMode::MaxBytes(_) => total * 2,
+        _ => total,
+    };
+    runner.report_progress(0, steps);
+    runner.check_version_loss(input);
+    let part_runner = runner.without_progress().without_warnings();
+
+    let chunks = match mode {
+        SplitMode::MaxBytes(max) => {
+            let sizes = measure_pages(runner, input, output_dir, &stem, total, steps)?;
+            group_by_size(&sizes, *max)?
+        }
+        _ => plan_chunks(mode, total)?,
+    };
+    if chunks.len() > 1 && !names_each_part(name_template) {
+        return Err(PdfToolError::InvalidArgument(format!(
+            "Name template '{}' gives every part the same name; add {{num}}, {{start}} or {{end}}",
+            name_template
+        )));
+    }
+
+    let mut outputs = Vec::new();
+    for (i, &(start, end)) in chunks.iter().enumerate() {
+        let name = render_name(name_template, &stem, i + 1, chunks.len(), start, end);
+        let output = output_dir.join(format!("{}.pdf", name));
+        let pages: Vec<u32> = (start..=end).collect();
+        if let Err(e) = extract_pages(&part_runner, input, &output, &pages) {
+            // Leave no partial set of parts behind.
+            for written in &outputs {
+                let _ = std::fs::remove_file(written);
+            }
+            return Err(e);
+        }
+        outputs.push(output);
+        runner.report_progress(steps - total + end, steps);
+    }
+
+    Ok(outputs)
+}
+
+/// Compute the (first, last) page of each part for the page-based modes.
+fn plan_chunks(mode: &SplitMode, total: u32) -> Result<Vec<(u32, u32)>, PdfToolError> {
+    if total == 0 {
+        return Err(PdfToolError::InvalidArgument(
+            "Document has no pages".to_string(),
+        ));
+    }
+
+    let mut starts: Vec<u32> = match mode {
+        SplitMode::EveryN(n) => {
+            if *n == 0 {
+                return Err(PdfToolError::InvalidArgument(
+                    "Pages per part must be greater than 0".to_string(),
+                ));
+            }
+            (1..=total).step_by(*n as usize).collect()
+        }
+        SplitMode::AtPages(pages) => {
+            validate_pages(pages, total)?;
+            let mut starts = vec![1];
+            starts.extend(pages.iter().copied().filter(|&p| p > 1));
+            starts
+        }
+        SplitMode::MaxBytes(_) => unreachable!("size-based splits are planned by group_by_size"),
+    };
+
+    starts.sort();
+    starts.dedup();
+
+    Ok(starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).map_or(total, |next| next - 1);
+            (start, end)
+        })
+        .collect())
+}
+
+/// Extract every page to a scratch directory and return the size of each one in bytes.
+fn measure_pages(
+    runner: &ToolRunner,
+    input: &Path,
+    output_dir: &Path,
+    stem: &str,
+    total: u32,
+    steps: u32,
+) -> Result<Vec<u64>, PdfToolError> {
+    let temp_dir = output_dir.join(format!("{}.split-tmp", stem));
+    std::fs::create_dir_all(&temp_dir)?;
+
+    let result = (|| -> Result<Vec<u64>, PdfToolError> {
+        let mut sizes = Vec::new();
+        for page in 1..=total {
+            let temp = temp_dir.join(format!("{}.pdf", page));
+            extract_pages(
+                &runner.without_progress().without_warnings(),
+                input,
+                &temp,
+                &[page],
+            )?;
+            sizes.push(std::fs::metadata(&temp)?.len());
+            let _ = std::fs::remove_file(&temp);
+            runner.report_progress(page, steps);
+        }
+        Ok(sizes)
+    })();
+
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    result
+}
+
+/// Group consecutive pages so that the summed size of each group stays within `max`.
+fn group_by_size(sizes: &[u64], max: u64) -> Result<Vec<(u32, u32)>, PdfToolError> {
+    if max == 0 {
+        return Err(PdfToolError::InvalidArgument(
+            "Maximum part size must be greater than 0".to_string(),
+        ));
+    }
+    if sizes.is_empty() {
+        return Err(PdfToolError::InvalidArgument(
+            "Document has no pages".to_string(),
+        ));
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 1;
+    let mut current = 0;
+
+    for (i, &size) in sizes.iter().enumerate() {
+        let page = i as u32 + 1;
+        if page > start && current + size > max {
+            chunks.push((start, page - 1));
+            start = page;
+            current = 0;
+        }
+        current += size;
+    }
+    chunks.push((start, sizes.len() as u32));
+
+    Ok(chunks)
+}
+
+/// Whether the template gives each part its own name.
+fn names_each_part(template: &str) -> bool {
+    ["{num}", "{start}", "{end}"]
+        .iter()
+        .any(|placeholder| template.contains(placeholder))
+}
+
+/// Fill in the placeholders of a split name template.
+fn render_name(
+    template: &str,
+    stem: &str,
+    index: usize,
+    count: usize,
+    start: u32,
+    end: u32,
+) -> String {
+    let width = count.to_string().len();
+    template
+        .replace("{name}", stem)
+        .replace("{num}", &format!("{:0width$}", index, width = width))
+        .replace("{start}", &start.to_string())
+        .replace("{end}", &end.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolOutput;
+    use crate::runner::fake::{FakeBackend, succeed};
+    use crate::test_pdf::{sample_pdf, temp_path};
+
+    #[test]
+    fn test_every_n() {
+        assert_eq!(
+            plan_chunks(&SplitMode::EveryN(10), 25).unwrap(),
+            vec![(1, 10), (11, 20), (21, 25)]
+        );
+    }
+
+    #[test]
+    fn test_every_n_zero_error() {
+        assert!(plan_chunks(&SplitMode::EveryN(0), 25).is_err());
+    }
+
+    #[test]
+    fn test_at_pages() {
+        assert_eq!(
+            plan_chunks(&SplitMode::AtPages(vec![1, 5, 10]), 12).unwrap(),
+            vec![(1, 4), (5, 9), (10, 12)]
+        );
+    }
+
+    #[test]
+    fn test_at_pages_out_of_range_error() {
+        assert!(plan_chunks(&SplitMode::AtPages(vec![5, 20]), 12).is_err());
+    }
+
+    #[test]
+    fn test_group_by_size() {
+        assert_eq!(
+            group_by_size(&[40, 40, 40, 150, 10], 100).unwrap(),
+            vec![(1, 2), (3, 3), (4, 4), (5, 5)]
+        );
+    }
+
+    #[test]
+    fn test_render_name() {
+        assert_eq!(
+            render_name(DEFAULT_SPLIT_TEMPLATE, "scan", 3, 12, 21, 30),
+            "scan_part03"
+        );
+        assert_eq!(
+            render_name("{name}-p{start}-{end}", "scan", 1, 2, 1, 10),
+            "scan-p1-10"
+        );
+    }
+
+    #[test]
+    fn test_template_without_part_number_error() {
+        let input = temp_path("split_same_name_in.pdf");
+        let output_dir = temp_path("split_same_name_out");
+        sample_pdf(&input, 4);
+        let fake = FakeBackend::succeeding();
+        let runner = ToolRunner::new(fake.clone());
+
+        let result = split_pdf(
+            &runner,
+            &input,
+            &output_dir,
+            &SplitMode::EveryN(2),
+            "chapter",
+        );
+        assert!(matches!(result, Err(PdfToolError::InvalidArgument(_))));
+        assert!(fake.calls().is_empty());
+        // A single part may use a fixed name.
+        let files = split_pdf(
+            &runner,
+            &input,
+            &output_dir,
+            &SplitMode::EveryN(4),
+            "chapter",
+        )
+        .unwrap();
+        assert_eq!(files, vec![output_dir.join("chapter.pdf")]);
+    }
+
+    #[test]
+    fn test_split_writes_every_part() {
+        let input = temp_path("split_parts_in.pdf");
+        let output_dir = temp_path("split_parts_out");
+        sample_pdf(&input, 5);
+        let fake = FakeBackend::succeeding();
+        let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        let runner = ToolRunner::new(fake.clone())
+            .on_progress(move |p| recorded.lock().unwrap().push(p.done));
+        let files = split_pdf(
+            &runner,
+            &input,
+            &output_dir,
+            &SplitMode::EveryN(2),
+            DEFAULT_SPLIT_TEMPLATE,
+        )
+        .unwrap();
+
+        assert_eq!(files.len(), 3);
+        assert!(files.iter().all(|f| f.exists()));
+        let page_lists: Vec<String> = fake
+            .calls()
+            .iter()
+            .filter_map(|(_, args)| args.iter().find(|a| a.starts_with("-sPageList=")).cloned())
+            .collect();
+        assert_eq!(
+            page_lists,
+            ["-sPageList=1,2", "-sPageList=3,4", "-sPageList=5"]
+        );
+        assert_eq!(*progress.lock().unwrap(), [0, 2, 4, 5]);
+    }
+
+    #[test]
+    fn test_failed_split_removes_written_parts() {
+        let input = temp_path("split_fail_in.pdf");
+        let output_dir = temp_path("split_fail_out");
+        sample_pdf(&input, 4);
+        // The second part fails after the first one was written.
+        let fake = FakeBackend::new(|tool, args| {
+            if args.contains(&"-sPageList=3,4".to_string()) {
+                return ToolOutput {
+                    code: Some(1),
+                    ..Default::default()
+                };
+            }
+            succeed(tool, args)
+        });
+        let runner = ToolRunner::new(fake);
+
+        let error = split_pdf(
+            &runner,
+            &input,
+            &output_dir,
+            &SplitMode::EveryN(2),
+            DEFAULT_SPLIT_TEMPLATE,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), "tool_failed");
+        assert!(!output_dir.join("split_fail_in_part1.pdf").exists());
+    }
+}
@@ -0,0 +1,52 @@
+//! Helpers for tests that need a real PDF on disk.
+
+use std::path::{Path, PathBuf};
+
+use lopdf::{Document, Object, Stream, dictionary};
+
+/// Path to a scratch file in the system temp directory.
+pub fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("pdftool-core-tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+/// Write an A4 document with `pages` pages, each showing its page number.
+pub fn sample_pdf(path: &Path, pages: u32) {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+    });
+
+    let mut kids: Vec<Object> = Vec::new();
+    for page in 1..=pages {
+        let content = format!("BT /F1 24 Tf 100 700 Td (Page {}) Tj ET", page);
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(page_id.into());
+    }
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => pages,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.save(path).unwrap();
+}
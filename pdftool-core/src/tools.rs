@@ -0,0 +1,358 @@
+use std::env::consts::EXE_SUFFIX;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// External programs pdftool drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tool {
+    Ghostscript,
+    Pdftotext,
+    Pandoc,
+    /// PDF engine for Pandoc (preferred when bundled)
+    Typst,
+    /// PDF engine for Pandoc (preferred over typst from PATH)
+    Pdflatex,
+}
+
+/// Where a tool's executable was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolSource {
+    /// Set explicitly through an environment variable such as `PDFTOOL_GS`
+    Env(&'static str),
+    /// Shipped next to our executable
+    Bundled,
+    /// A well-known install location outside PATH
+    KnownLocation,
+    /// Found by searching PATH
+    Path,
+}
+
+impl fmt::Display for ToolSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolSource::Env(var) => write!(f, "from {}", var),
+            ToolSource::Bundled => write!(f, "bundled"),
+            ToolSource::KnownLocation => write!(f, "known install location"),
+            ToolSource::Path => write!(f, "found in PATH"),
+        }
+    }
+}
+
+/// The executable chosen for a tool, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Located {
+    pub tool: Tool,
+    pub path: PathBuf,
+    pub source: ToolSource,
+}
+
+impl fmt::Display for Located {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} ({})",
+            self.tool.name(),
+            self.path.display(),
+            self.source
+        )
+    }
+}
+
+impl Tool {
+    pub const ALL: [Tool; 5] = [
+        Tool::Ghostscript,
+        Tool::Pdftotext,
+        Tool::Pandoc,
+        Tool::Typst,
+        Tool::Pdflatex,
+    ];
+
+    /// Name used in messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Ghostscript => "Ghostscript",
+            Tool::Pdftotext => "pdftotext",
+            Tool::Pandoc => "pandoc",
+            Tool::Typst => "typst",
+            Tool::Pdflatex => "pdflatex",
+        }
+    }
+
+    /// Environment variable that overrides discovery with an explicit path.
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Tool::Ghostscript => "PDFTOOL_GS",
+            Tool::Pdftotext => "PDFTOOL_PDFTOTEXT",
+            Tool::Pandoc => "PDFTOOL_PANDOC",
+            Tool::Typst => "PDFTOOL_TYPST",
+            Tool::Pdflatex => "PDFTOOL_PDFLATEX",
+        }
+    }
+
+    /// Executable file name, without the platform suffix.
+    fn exe_name(self) -> &'static str {
+        match self {
+            Tool::Ghostscript if cfg!(target_os = "windows") => "gswin64c",
+            Tool::Ghostscript => "gs",
+            other => other.name(),
+        }
+    }
+
+    /// Path of the executable relative to a bundle directory.
+    fn bundled_path(self) -> PathBuf {
+        let file = format!("{}{}", self.exe_name(), EXE_SUFFIX);
+        match self {
+            Tool::Ghostscript => Path::new("ghostscript").join("bin").join(file),
+            Tool::Pandoc | Tool::Typst => Path::new("pandoc").join(file),
+            Tool::Pdftotext | Tool::Pdflatex => PathBuf::from(file),
+        }
+    }
+
+    /// Find the executable for this tool.
+    /// Priority: environment override → bundled next to our executable →
+    /// PATH → known install locations.
+    pub fn locate(self) -> Option<Located> {
+        self.locate_with_override(std::env::var_os(self.env_var()))
+    }
+
+    fn locate_with_override(self, env_value: Option<OsString>) -> Option<Located> {
+        let found = |path: PathBuf, source: ToolSource| Located {
+            tool: self,
+            path,
+            source,
+        };
+
+        // An explicit override is used as-is, so a wrong path fails loudly
+        // instead of silently falling back to another copy.
+        if let Some(path) = env_value.filter(|p| !p.is_empty()) {
+            return Some(found(PathBuf::from(path), ToolSource::Env(self.env_var())));
+        }
+
+        let bundled = self.bundled_path();
+        if let Some(path) = bundle_dirs()
+            .into_iter()
+            .map(|dir| dir.join(&bundled))
+            .find(|p| p.is_file())
+        {
+            return Some(found(path, ToolSource::Bundled));
+        }
+
+        if let Some(path) = find_in_path(self.exe_name()) {
+            return Some(found(path, ToolSource::Path));
+        }
+
+        self.known_locations()
+            .into_iter()
+            .find(|p| p.is_file())
+            .map(|path| found(path, ToolSource::KnownLocation))
+    }
+
+    /// Install locations that are often missing from PATH (GUI apps on macOS
+    /// do not inherit the shell's PATH, for instance).
+    fn known_locations(self) -> Vec<PathBuf> {
+        if cfg!(target_os = "windows") {
+            let local_app = std::env::var_os("LOCALAPPDATA").map(PathBuf::from);
+            match self {
+                Tool::Pdftotext => vec![
+                    PathBuf::from(r"C:\Program Files\Git\mingw64\bin\pdftotext.exe"),
+                    PathBuf::from(r"C:\Program Files (x86)\Git\mingw64\bin\pdftotext.exe"),
+                ],
+                Tool::Pandoc => local_app
+                    .map(|dir| dir.join("Pandoc").join("pandoc.exe"))
+                    .into_iter()
+                    .collect(),
+                Tool::Typst => local_app
+                    .and_then(|dir| winget_typst(&dir))
+                    .into_iter()
+                    .collect(),
+                Tool::Ghostscript | Tool::Pdflatex => Vec::new(),
+            }
+        } else if cfg!(target_os = "macos") {
+            ["/opt/homebrew/bin", "/usr/local/bin", "/Library/TeX/texbin"]
+                .iter()
+                .map(|dir| Path::new(dir).join(self.exe_name()))
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Directories that may hold bundled tools:
+/// - next to our executable (Windows installs, portable archives)
+/// - `../Resources` inside a macOS app bundle
+/// - `../lib/pdftool` for Linux packages installing the binary in `bin/`
+fn bundle_dirs() -> Vec<PathBuf> {
+    let Some(exe_dir) = exe_dir() else {
+        return Vec::new();
+    };
+    let mut dirs = vec![exe_dir.clone()];
+    if let Some(parent) = exe_dir.parent() {
+        if cfg!(target_os = "macos") {
+            dirs.push(parent.join("Resources"));
+        } else if cfg!(unix) {
+            dirs.push(parent.join("lib").join("pdftool"));
+        }
+    }
+    dirs
+}
+
+/// Return the path to the bundled GS directory, if it exists.
+/// Ghostscript needs its lib/ and Resource/ folders to find its init files.
+pub(crate) fn gs_lib_path() -> Option<PathBuf> {
+    bundle_dirs()
+        .into_iter()
+        .map(|dir| dir.join("ghostscript"))
+        .find(|gs_dir| gs_dir.join("lib").exists() && gs_dir.join("Resource").exists())
+}
+
+/// Value for `GS_LIB` pointing at a bundled Ghostscript's support folders,
+/// joined with the platform's path-list separator.
+pub(crate) fn gs_lib_env(gs_dir: &Path) -> Option<OsString> {
+    let dirs = ["lib", "Resource", "iccprofiles"].map(|name| gs_dir.join(name));
+    std::env::join_paths(dirs).ok()
+}
+
+/// Environment variable naming the ICC profile used for PDF/A output intents.
+pub(crate) const ICC_PROFILE_ENV: &str = "PDFTOOL_ICC_PROFILE";
+
+/// Find Ghostscript's sRGB profile: `PDFTOOL_ICC_PROFILE` if set, then the
+/// bundled `iccprofiles` folder, then the one installed with `gs_exe`
+/// (`<prefix>/iccprofiles` on Windows, `<prefix>/share/ghostscript/<version>/iccprofiles`
+/// elsewhere).
+pub(crate) fn icc_profile(gs_exe: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(ICC_PROFILE_ENV).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    let mut dirs: Vec<PathBuf> = gs_lib_path()
+        .map(|dir| dir.join("iccprofiles"))
+        .into_iter()
+        .collect();
+    if let Some(prefix) = gs_exe.and_then(Path::parent).and_then(Path::parent) {
+        dirs.push(prefix.join("iccprofiles"));
+        let pattern = prefix
+            .join("share")
+            .join("ghostscript")
+            .join("*")
+            .join("iccprofiles");
+        if let Ok(found) = glob::glob(&pattern.to_string_lossy()) {
+            // Newest version last
+            let mut found: Vec<PathBuf> = found.flatten().collect();
+            found.sort();
+            dirs.extend(found.into_iter().rev());
+        }
+    }
+    dirs.into_iter()
+        .map(|dir| dir.join("srgb.icc"))
+        .find(|p| p.is_file())
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    exe.parent().map(Path::to_path_buf)
+}
+
+/// Search the directories of PATH for an executable.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{}{}", name, EXE_SUFFIX);
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(&file_name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Typst installed with `winget install typst` lives in a versioned package folder.
+fn winget_typst(local_app: &Path) -> Option<PathBuf> {
+    let winget_dir = local_app.join("Microsoft\\WinGet\\Packages");
+    for entry in std::fs::read_dir(&winget_dir).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with("Typst.Typst")
+            && let Some(path) = find_exe_in_dir(&entry.path(), "typst.exe")
+        {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Recursively search for an executable in a directory.
+fn find_exe_in_dir(dir: &Path, exe_name: &str) -> Option<PathBuf> {
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                if let Some(name) = path.file_name()
+                    && name.to_string_lossy().eq_ignore_ascii_case(exe_name)
+                {
+                    return Some(path);
+                }
+            } else if path.is_dir()
+                && let Some(found) = find_exe_in_dir(&path, exe_name)
+            {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_override_wins() {
+        let located = Tool::Pdflatex
+            .locate_with_override(Some(OsString::from("/opt/custom/pdflatex")))
+            .unwrap();
+
+        assert_eq!(located.path, PathBuf::from("/opt/custom/pdflatex"));
+        assert_eq!(located.source, ToolSource::Env("PDFTOOL_PDFLATEX"));
+        assert_eq!(
+            located.to_string(),
+            "pdflatex: /opt/custom/pdflatex (from PDFTOOL_PDFLATEX)"
+        );
+    }
+
+    #[test]
+    fn test_empty_override_is_ignored() {
+        let located = Tool::Pdflatex.locate_with_override(Some(OsString::new()));
+        assert!(located.is_none_or(|l| l.source != ToolSource::Env("PDFTOOL_PDFLATEX")));
+    }
+
+    #[test]
+    fn test_icc_profile_next_to_installed_gs() {
+        let prefix = std::env::temp_dir().join("pdftool-icc-test");
+        let profiles = prefix
+            .join("share")
+            .join("ghostscript")
+            .join("10.02")
+            .join("iccprofiles");
+        std::fs::create_dir_all(&profiles).unwrap();
+        std::fs::write(profiles.join("srgb.icc"), b"icc").unwrap();
+
+        let gs = prefix.join("bin").join("gs");
+        // PDFTOOL_ICC_PROFILE would take precedence
+        if std::env::var_os(ICC_PROFILE_ENV).is_none() {
+            assert_eq!(icc_profile(Some(&gs)), Some(profiles.join("srgb.icc")));
+            assert_eq!(icc_profile(Some(Path::new("gs"))), None);
+        }
+    }
+
+    #[test]
+    fn test_gs_lib_uses_platform_separator() {
+        let value = gs_lib_env(Path::new("gs")).unwrap();
+        let dirs: Vec<PathBuf> = std::env::split_paths(&value).collect();
+        assert_eq!(
+            dirs,
+            [
+                Path::new("gs/lib"),
+                Path::new("gs/Resource"),
+                Path::new("gs/iccprofiles")
+            ]
+            .map(|p| p.iter().collect::<PathBuf>())
+        );
+    }
+}
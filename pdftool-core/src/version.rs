@@ -0,0 +1,270 @@
+use std::fmt;
+use std::path::Path;
+
+use lopdf::{Dictionary, Document, Object};
+use serde::{Deserialize, Serialize};
+
+use crate::compress::run_gs_to_pdf;
+use crate::{PdfToolError, ToolRunner, discard_on_error, page_count};
+
+/// A PDF version Ghostscript can write. Serialized as its "1.4"-style name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PdfVersion {
+    major: u8,
+    minor: u8,
+}
+
+impl PdfVersion {
+    pub const ALL: [PdfVersion; 6] = [
+        PdfVersion { major: 1, minor: 3 },
+        PdfVersion { major: 1, minor: 4 },
+        PdfVersion { major: 1, minor: 5 },
+        PdfVersion { major: 1, minor: 6 },
+        PdfVersion { major: 1, minor: 7 },
+        PdfVersion { major: 2, minor: 0 },
+    ];
+
+    /// Parse "1.3" to "1.7" or "2.0".
+    pub fn parse(version: &str) -> Result<Self, PdfToolError> {
+        let version = version.trim();
+        PdfVersion::ALL
+            .into_iter()
+            .find(|v| v.to_string() == version)
+            .ok_or_else(|| {
+                let valid: Vec<String> = PdfVersion::ALL.iter().map(ToString::to_string).collect();
+                PdfToolError::InvalidArgument(format!(
+                    "Invalid PDF version '{}'. Must be one of: {}",
+                    version,
+                    valid.join(", ")
+                ))
+            })
+    }
+
+    const fn new(major: u8, minor: u8) -> Self {
+        PdfVersion { major, minor }
+    }
+}
+
+impl fmt::Display for PdfVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl TryFrom<String> for PdfVersion {
+    type Error = PdfToolError;
+
+    fn try_from(version: String) -> Result<Self, Self::Error> {
+        PdfVersion::parse(&version)
+    }
+}
+
+impl From<PdfVersion> for String {
+    fn from(version: PdfVersion) -> Self {
+        version.to_string()
+    }
+}
+
+/// Rewrite `input` as a PDF of the given version. `output` may be the input
+/// itself, which is then replaced once the new file is complete.
+///
+/// Returns a warning for each feature of the document the version does not
+/// support (see `version_warnings`); the conversion still happens.
+pub fn set_pdf_version(
+    runner: &ToolRunner,
+    input: &Path,
+    output: &Path,
+    version: PdfVersion,
+) -> Result<Vec<String>, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+    let warnings = version_warnings(input, version)?;
+
+    let in_place = input == output;
+    let target = if in_place {
+        output.with_extension("version-tmp.pdf")
+    } else {
+        output.to_path_buf()
+    };
+    let runner = runner.clone().with_pdf_version(version);
+    let args = [
+        runner.pdfwrite_args(),
+        ["-dNOPAUSE", "-dBATCH", "-dSAFER"]
+            .map(String::from)
+            .to_vec(),
+    ]
+    .concat();
+    let total = page_count(input).unwrap_or(0);
+    run_gs_to_pdf(&runner, input, &target, args, None, total)?;
+
+    if in_place && let Err(e) = std::fs::rename(&target, output) {
+        let _ = std::fs::remove_file(&target);
+        return Err(e.into());
+    }
+    Ok(warnings)
+}
+
+/// Rewrite `output`, just written without Ghostscript, at the runner's PDF version
+/// if it has one. Warnings go to the runner's hook; `output` is removed on failure.
+pub(crate) fn apply_pdf_version(runner: &ToolRunner, output: &Path) -> Result<(), PdfToolError> {
+    let Some(version) = runner.pdf_version() else {
+        return Ok(());
+    };
+    runner.check_version_loss(output);
+    let result = set_pdf_version(&runner.without_warnings(), output, output, version);
+    discard_on_error(result, output)?;
+    Ok(())
+}
+
+/// Features of `input` that `version` cannot represent, as messages saying what
+/// will happen to them. Empty when nothing is lost, including for files lopdf
+/// cannot read (Ghostscript may still manage).
+pub fn version_warnings(input: &Path, version: PdfVersion) -> Result<Vec<String>, PdfToolError> {
+    if !input.exists() {
+        return Err(PdfToolError::InputNotFound(input.to_path_buf()));
+    }
+    let Ok(doc) = Document::load(input) else {
+        return Ok(Vec::new());
+    };
+    Ok(features(&doc)
+        .into_iter()
+        .filter(|feature| feature.since > version)
+        .map(|feature| {
+            format!(
+                "{} needs PDF {} or later; writing PDF {} {}",
+                feature.name, feature.since, version, feature.loss
+            )
+        })
+        .collect())
+}
+
+struct Feature {
+    name: &'static str,
+    since: PdfVersion,
+    /// What Ghostscript does with it when writing an older version
+    loss: &'static str,
+}
+
+const TRANSPARENCY: Feature = Feature {
+    name: "Transparency",
+    since: PdfVersion::new(1, 4),
+    loss: "flattens it, which can turn parts of pages into images",
+};
+const JPEG2000: Feature = Feature {
+    name: "JPEG 2000 images",
+    since: PdfVersion::new(1, 5),
+    loss: "re-encodes them",
+};
+const LAYERS: Feature = Feature {
+    name: "Optional content (layers)",
+    since: PdfVersion::new(1, 5),
+    loss: "merges every layer into the page",
+};
+
+/// Version-dependent features used by the document.
+fn features(doc: &Document) -> Vec<Feature> {
+    let (mut transparency, mut jpeg2000) = (false, false);
+    for object in doc.objects.values() {
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &stream.dict,
+            _ => continue,
+        };
+        transparency |= uses_transparency(dict);
+        jpeg2000 |= has_filter(dict, b"JPXDecode");
+    }
+    let layers = doc
+        .catalog()
+        .is_ok_and(|catalog| catalog.has(b"OCProperties"));
+
+    [
+        (transparency, TRANSPARENCY),
+        (jpeg2000, JPEG2000),
+        (layers, LAYERS),
+    ]
+    .into_iter()
+    .filter_map(|(used, feature)| used.then_some(feature))
+    .collect()
+}
+
+/// Soft masks, constant alpha below 1, blend modes and transparency groups.
+fn uses_transparency(dict: &Dictionary) -> bool {
+    let name = |key: &[u8]| dict.get(key).and_then(Object::as_name).ok();
+    let below_one = |key: &[u8]| {
+        dict.get(key)
+            .and_then(Object::as_float)
+            .is_ok_and(|a| a < 1.0)
+    };
+    let soft_mask = dict.has(b"SMask") && name(b"SMask") != Some(b"None".as_slice());
+    let blend = name(b"BM").is_some_and(|bm| bm != b"Normal" && bm != b"Compatible");
+    let group = dict
+        .get(b"Group")
+        .and_then(Object::as_dict)
+        .and_then(|group| group.get(b"S"))
+        .and_then(Object::as_name)
+        .is_ok_and(|s| s == b"Transparency");
+    soft_mask || blend || group || below_one(b"CA") || below_one(b"ca")
+}
+
+fn has_filter(dict: &Dictionary, filter: &[u8]) -> bool {
+    match dict.get(b"Filter") {
+        Ok(Object::Name(name)) => name == filter,
+        Ok(Object::Array(filters)) => filters
+            .iter()
+            .any(|f| f.as_name().is_ok_and(|n| n == filter)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::fake::FakeBackend;
+    use crate::test_pdf::{sample_pdf, temp_path};
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(PdfVersion::parse("1.4").unwrap().to_string(), "1.4");
+        assert!(PdfVersion::parse("1.3").unwrap() < PdfVersion::parse("2.0").unwrap());
+        assert!(PdfVersion::parse("1.8").is_err());
+    }
+
+    #[test]
+    fn test_downgrade_warns_about_transparency() {
+        let input = temp_path("version_transparency.pdf");
+        sample_pdf(&input, 1);
+        let mut doc = Document::load(&input).unwrap();
+        doc.add_object(dictionary! { "Type" => "ExtGState", "ca" => 0.5 });
+        doc.save(&input).unwrap();
+
+        let warnings = version_warnings(&input, PdfVersion::parse("1.3").unwrap()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Transparency needs PDF 1.4"));
+        assert!(
+            version_warnings(&input, PdfVersion::parse("1.4").unwrap())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_set_version_in_place() {
+        let input = temp_path("version_in_place.pdf");
+        sample_pdf(&input, 2);
+        let fake = FakeBackend::succeeding();
+        let version = PdfVersion::parse("1.6").unwrap();
+
+        set_pdf_version(&ToolRunner::new(fake.clone()), &input, &input, version).unwrap();
+        let args = &fake.calls()[0].1;
+        assert!(args.contains(&"-dCompatibilityLevel=1.6".to_string()));
+        assert!(
+            args.iter()
+                .any(|a| a.ends_with("version_in_place.version-tmp.pdf"))
+        );
+        assert!(input.exists());
+        assert!(!input.with_extension("version-tmp.pdf").exists());
+    }
+}
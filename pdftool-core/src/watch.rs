@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{PdfToolError, ToolRunner};
+
+/// Settings for `watch_folder`.
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// How often the folder is scanned
+    pub interval: Duration,
+    /// How long a file must keep the same size and modification time before it
+    /// is considered completely written
+    pub settle: Duration,
+    /// Where originals go once processed
+    pub processed_dir: PathBuf,
+    /// Where originals go when processing failed
+    pub failed_dir: PathBuf,
+    /// File every result is appended to
+    pub log: PathBuf,
+}
+
+impl WatchOptions {
+    /// Defaults for watching `dir`: scan every second, wait 2 seconds for writes
+    /// to finish, and keep `processed/`, `failed/` and `pdftool-watch.log` inside `dir`.
+    pub fn new(dir: &Path) -> Self {
+        WatchOptions {
+            interval: Duration::from_secs(1),
+            settle: Duration::from_secs(2),
+            processed_dir: dir.join("processed"),
+            failed_dir: dir.join("failed"),
+            log: dir.join("pdftool-watch.log"),
+        }
+    }
+}
+
+/// Process every PDF that appears (or is replaced) in `dir` until `runner` is
+/// cancelled. Only files directly inside `dir` are considered, so outputs and the
+/// processed/failed folders may live in subfolders.
+///
+/// `process` runs once a file has stopped changing. The original is then moved to
+/// `processed_dir`, or to `failed_dir` if `process` returned an error, and a line is
+/// appended to the log and passed to `on_log`. A cancelled operation leaves the
+/// original where it is.
+pub fn watch_folder(
+    runner: &ToolRunner,
+    dir: &Path,
+    options: &WatchOptions,
+    mut process: impl FnMut(&Path) -> Result<String, PdfToolError>,
+    mut on_log: impl FnMut(&str),
+) -> Result<(), PdfToolError> {
+    if !dir.is_dir() {
+        return Err(PdfToolError::InputNotFound(dir.to_path_buf()));
+    }
+    let mut settling = Settling::default();
+
+    while runner.check_cancelled().is_ok() {
+        for file in settling.ready(scan(dir)?, Instant::now(), options.settle) {
+            let result = process(&file);
+            if matches!(result, Err(PdfToolError::Cancelled)) {
+                return Ok(());
+            }
+
+            let (status, detail, target_dir) = match &result {
+                Ok(message) => ("OK", message.clone(), &options.processed_dir),
+                Err(e) => ("FAILED", e.to_string(), &options.failed_dir),
+            };
+            let moved = match move_into(&file, target_dir) {
+                Ok(moved) => format!("moved to {}", moved.display()),
+                Err(e) => format!("could not move it: {}", e),
+            };
+            let line = format!(
+                "{} {} {}: {} ({})",
+                timestamp(SystemTime::now()),
+                status,
+                file.display(),
+                detail,
+                moved
+            );
+            append_line(&options.log, &line)?;
+            on_log(&line);
+        }
+        thread::sleep(options.interval);
+    }
+    Ok(())
+}
+
+/// PDF files directly inside `dir`, with their size and modification time.
+fn scan(dir: &Path) -> Result<Vec<(PathBuf, u64, Option<SystemTime>)>, PdfToolError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let is_pdf = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"));
+        if let Ok(metadata) = entry.metadata()
+            && metadata.is_file()
+            && is_pdf
+        {
+            files.push((path, metadata.len(), metadata.modified().ok()));
+        }
+    }
+    Ok(files)
+}
+
+/// Tracks files until they have stopped changing.
+#[derive(Default)]
+struct Settling {
+    seen: HashMap<PathBuf, Seen>,
+}
+
+struct Seen {
+    size: u64,
+    modified: Option<SystemTime>,
+    /// When this size and modification time were first seen
+    since: Instant,
+    reported: bool,
+}
+
+impl Settling {
+    /// Files of the latest scan that have not changed for `settle`.
+    /// A file that changes again later is reported again.
+    fn ready(
+        &mut self,
+        files: Vec<(PathBuf, u64, Option<SystemTime>)>,
+        now: Instant,
+        settle: Duration,
+    ) -> Vec<PathBuf> {
+        self.seen
+            .retain(|path, _| files.iter().any(|(p, _, _)| p == path));
+        let mut ready = Vec::new();
+        for (path, size, modified) in files {
+            match self.seen.get_mut(&path) {
+                Some(seen) if seen.size == size && seen.modified == modified => {
+                    if !seen.reported && now.duration_since(seen.since) >= settle {
+                        seen.reported = true;
+                        ready.push(path);
+                    }
+                }
+                _ => {
+                    let seen = Seen {
+                        size,
+                        modified,
+                        since: now,
+                        reported: false,
+                    };
+                    self.seen.insert(path, seen);
+                }
+            }
+        }
+        ready.sort();
+        ready
+    }
+}
+
+/// Move `file` into `dir`, adding a number to its name if that name is taken.
+fn move_into(file: &Path, dir: &Path) -> Result<PathBuf, PdfToolError> {
+    fs::create_dir_all(dir)?;
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = file.extension().unwrap_or_default().to_string_lossy();
+    let mut target = dir.join(file.file_name().unwrap_or_default());
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{}_{}.{}", stem, n, ext));
+        n += 1;
+    }
+    // rename fails across file systems; fall back to copying.
+    if fs::rename(file, &target).is_err() {
+        fs::copy(file, &target)?;
+        fs::remove_file(file)?;
+    }
+    Ok(target)
+}
+
+fn append_line(log: &Path, line: &str) -> Result<(), PdfToolError> {
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(log)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// "YYYY-MM-DD HH:MM:SS" in UTC.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, rest) = (secs / 86400, secs % 86400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_pdf::temp_path;
+
+    #[test]
+    fn test_waits_until_file_stops_changing() {
+        let mut settling = Settling::default();
+        let settle = Duration::from_secs(2);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let scan = |size| vec![(PathBuf::from("scan.pdf"), size, None)];
+
+        assert!(settling.ready(scan(100), at(0), settle).is_empty());
+        // Still growing
+        assert!(settling.ready(scan(200), at(2), settle).is_empty());
+        assert!(settling.ready(scan(200), at(3), settle).is_empty());
+        assert_eq!(
+            settling.ready(scan(200), at(4), settle),
+            [PathBuf::from("scan.pdf")]
+        );
+        // Reported once
+        assert!(settling.ready(scan(200), at(10), settle).is_empty());
+        // Replaced by a new version
+        assert!(settling.ready(scan(300), at(11), settle).is_empty());
+        assert_eq!(settling.ready(scan(300), at(13), settle).len(), 1);
+    }
+
+    #[test]
+    fn test_move_into_keeps_existing_files() {
+        let dir = temp_path("watch-processed");
+        let _ = fs::remove_dir_all(&dir);
+        let file = temp_path("watch-scan.pdf");
+
+        fs::write(&file, b"first").unwrap();
+        assert_eq!(move_into(&file, &dir).unwrap(), dir.join("watch-scan.pdf"));
+        fs::write(&file, b"second").unwrap();
+        assert_eq!(
+            move_into(&file, &dir).unwrap(),
+            dir.join("watch-scan_1.pdf")
+        );
+        assert!(!file.exists());
+        assert_eq!(fs::read(dir.join("watch-scan.pdf")).unwrap(), b"first");
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(timestamp(time), "2023-11-14 22:13:20");
+        assert_eq!(timestamp(SystemTime::UNIX_EPOCH), "1970-01-01 00:00:00");
+    }
+}
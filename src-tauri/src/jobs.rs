@@ -0,0 +1,238 @@
+//! Background job queue for the GUI. Commands enqueue their work and return at
+//! once; worker threads run the jobs and report every change through `emit`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use pdftool_core::{CancelToken, PdfToolError, PdfVersion, Progress, ToolRunner};
+use serde::Serialize;
+
+pub type JobId = u64;
+
+/// The work of a job. Kept after it runs so the job can be retried.
+type Work = dyn Fn(&ToolRunner) -> Result<String, PdfToolError> + Send + Sync;
+type Emit = dyn Fn(&JobInfo) + Send + Sync;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+/// What the frontend sees of a job, sent with every `job` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobInfo {
+    pub id: JobId,
+    /// Operation name, e.g. "compress"
+    pub kind: String,
+    /// Short description for the job list, usually the input file name
+    pub label: String,
+    pub status: JobStatus,
+    pub progress: Option<Progress>,
+    /// Success message of a finished job
+    pub message: Option<String>,
+    /// Serialized `PdfToolError` of a failed job
+    pub error: Option<serde_json::Value>,
+}
+
+struct Job {
+    info: JobInfo,
+    work: Arc<Work>,
+    cancel: CancelToken,
+    /// PDF version chosen when the job was added, kept for retries
+    pdf_version: Option<PdfVersion>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: JobId,
+    jobs: BTreeMap<JobId, Job>,
+    pending: VecDeque<JobId>,
+    pdf_version: Option<PdfVersion>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    wake: Condvar,
+    emit: Box<Emit>,
+}
+
+/// Queue of operations run by a fixed number of worker threads.
+pub struct JobQueue {
+    shared: Arc<Shared>,
+}
+
+impl JobQueue {
+    /// Start `workers` threads. At most that many jobs run at the same time.
+    pub fn new(workers: usize, emit: impl Fn(&JobInfo) + Send + Sync + 'static) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            wake: Condvar::new(),
+            emit: Box::new(emit),
+        });
+        for _ in 0..workers.max(1) {
+            let shared = shared.clone();
+            thread::spawn(move || worker(&shared));
+        }
+        JobQueue { shared }
+    }
+
+    /// PDF version of the files written by the jobs added from now on; `None`
+    /// leaves it to each operation.
+    pub fn set_pdf_version(&self, version: Option<PdfVersion>) {
+        self.shared.state.lock().unwrap().pdf_version = version;
+    }
+
+    /// Add a job and return its ID. It starts as soon as a worker is free.
+    pub fn enqueue(
+        &self,
+        kind: &str,
+        label: String,
+        work: impl Fn(&ToolRunner) -> Result<String, PdfToolError> + Send + Sync + 'static,
+    ) -> JobId {
+        let mut state = self.shared.state.lock().unwrap();
+        state.next_id += 1;
+        let id = state.next_id;
+        let pdf_version = state.pdf_version;
+        let info = JobInfo {
+            id,
+            kind: kind.to_string(),
+            label,
+            status: JobStatus::Queued,
+            progress: None,
+            message: None,
+            error: None,
+        };
+        (self.shared.emit)(&info);
+        state.jobs.insert(
+            id,
+            Job {
+                info,
+                work: Arc::new(work),
+                cancel: CancelToken::new(),
+                pdf_version,
+            },
+        );
+        state.pending.push_back(id);
+        self.shared.wake.notify_one();
+        id
+    }
+
+    /// All jobs, oldest first.
+    pub fn list(&self) -> Vec<JobInfo> {
+        let state = self.shared.state.lock().unwrap();
+        state.jobs.values().map(|job| job.info.clone()).collect()
+    }
+
+    /// Cancel a queued or running job. A running job stops once its tool is killed.
+    pub fn cancel(&self, id: JobId) -> Result<(), String> {
+        let mut state = self.shared.state.lock().unwrap();
+        let job = state
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| format!("Unknown job {}", id))?;
+        match job.info.status {
+            JobStatus::Queued => {
+                job.info.status = JobStatus::Cancelled;
+                (self.shared.emit)(&job.info);
+                state.pending.retain(|&pending| pending != id);
+            }
+            JobStatus::Running => job.cancel.cancel(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Queue a failed or cancelled job again, with the same ID.
+    pub fn retry(&self, id: JobId) -> Result<(), String> {
+        let mut state = self.shared.state.lock().unwrap();
+        let job = state
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| format!("Unknown job {}", id))?;
+        if !matches!(job.info.status, JobStatus::Failed | JobStatus::Cancelled) {
+            return Err(format!("Job {} has not failed", id));
+        }
+        job.cancel = CancelToken::new();
+        job.info.status = JobStatus::Queued;
+        job.info.progress = None;
+        job.info.error = None;
+        (self.shared.emit)(&job.info);
+        state.pending.push_back(id);
+        self.shared.wake.notify_one();
+        Ok(())
+    }
+}
+
+fn worker(shared: &Arc<Shared>) {
+    loop {
+        let (id, work, cancel, pdf_version) = {
+            let mut state = shared.state.lock().unwrap();
+            let id = loop {
+                if let Some(id) = state.pending.pop_front() {
+                    break id;
+                }
+                state = shared.wake.wait(state).unwrap();
+            };
+            let job = state.jobs.get_mut(&id).expect("queued job exists");
+            job.info.status = JobStatus::Running;
+            (shared.emit)(&job.info);
+            (id, job.work.clone(), job.cancel.clone(), job.pdf_version)
+        };
+
+        let progress_shared = shared.clone();
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let recorded = warnings.clone();
+        let mut runner = ToolRunner::default()
+            .with_cancel(cancel)
+            .on_progress(move |progress| {
+                update(&progress_shared, id, |info| info.progress = Some(progress));
+            })
+            .on_warning(move |warning| recorded.lock().unwrap().push(warning.to_string()));
+        if let Some(version) = pdf_version {
+            runner = runner.with_pdf_version(version);
+        }
+
+        let result = work(&runner);
+        let warnings = std::mem::take(&mut *warnings.lock().unwrap());
+        update(shared, id, |info| match result {
+            Ok(mut message) => {
+                for warning in warnings {
+                    message.push_str(&format!(". Warning: {}", warning));
+                }
+                info.status = JobStatus::Done;
+                info.message = Some(message);
+            }
+            Err(PdfToolError::Cancelled) => info.status = JobStatus::Cancelled,
+            Err(e) => {
+                info.status = JobStatus::Failed;
+                info.error = serde_json::to_value(&e).ok();
+            }
+        });
+    }
+}
+
+/// Change a job's info and emit the result.
+fn update(shared: &Shared, id: JobId, change: impl FnOnce(&mut JobInfo)) {
+    let mut state = shared.state.lock().unwrap();
+    if let Some(job) = state.jobs.get_mut(&id)
+        && !job.info.status.is_finished()
+    {
+        change(&mut job.info);
+        (shared.emit)(&job.info);
+    }
+}
@@ -1,7 +1,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use serde::Serialize;
 use std::path::PathBuf;
-use pdftool_core::{compress_pdf, convert_pdf, extract_pages, extract_text, md_to_pdf, pdf_to_md, parse_page_range};
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+
+mod jobs;
+use jobs::{JobId, JobInfo, JobQueue};
+use pdftool_core::{
+    ColorMode, CompressOptions, DEFAULT_SPLIT_TEMPLATE, EnvironmentReport, PdfInfo, PdfToolError,
+    PdfVersion, PdfaLevel, Pipeline, ReorderMode, SplitMode, ToolRunner, check_environment,
+    compress_pdf_with, compress_to_size, convert_color, convert_pdf, extract_pages, extract_text,
+    inspect_pdf, md_to_pdf, merge_pdfs, page_count, parse_merge_input, pdf_to_md,
+    render_output_name, reorder_pages, resolve_page_range, resolve_page_sequence, rotate_pages,
+    set_pdf_version, split_pdf, to_pdfa,
+};
+
+/// Number of operations the GUI runs at the same time.
+const MAX_JOBS: usize = 2;
 
 fn downloads_dir() -> PathBuf {
     dirs::download_dir().unwrap_or_else(|| PathBuf::from("."))
@@ -17,6 +33,91 @@ fn strip_extension(name: &str) -> String {
         .to_string()
 }
 
+/// Short description of a job for the job list.
+fn file_label(input: &str) -> String {
+    std::path::Path::new(input)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Output name template for a group of inputs: the name typed by the user, or
+/// `default` when it is empty. With several inputs, a name without placeholders
+/// gets `_{num}` so the outputs do not overwrite each other.
+fn name_template(output_name: &str, default: &str, count: usize) -> String {
+    if output_name.is_empty() {
+        return default.to_string();
+    }
+    let name = strip_extension(output_name);
+    if count > 1 && !name.contains('{') {
+        format!("{}_{{num}}", name)
+    } else {
+        name
+    }
+}
+
+/// Enqueue one job per input file. `work` gets the input and its position, from 1.
+fn enqueue_each(
+    jobs: &JobQueue,
+    kind: &str,
+    inputs: Vec<String>,
+    work: impl Fn(&ToolRunner, &str, usize) -> Result<String, PdfToolError> + Send + Sync + 'static,
+) -> Vec<JobId> {
+    let work = Arc::new(work);
+    inputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let work = work.clone();
+            jobs.enqueue(kind, file_label(&input), move |runner| {
+                work(runner, &input, i + 1)
+            })
+        })
+        .collect()
+}
+
+/// Folder whose recipes the Pipeline tab offers as presets.
+fn recipes_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("pdftool")
+        .join("recipes")
+}
+
+/// A recipe file as shown in the Pipeline tab.
+#[derive(Serialize)]
+struct RecipeInfo {
+    path: PathBuf,
+    /// The recipe's name, or its file name
+    name: String,
+    description: Option<String>,
+    /// Readable summary of each step
+    steps: Vec<String>,
+    /// Why the recipe cannot be used
+    error: Option<String>,
+}
+
+fn recipe_info(path: PathBuf) -> RecipeInfo {
+    let file_name = file_label(&path.to_string_lossy());
+    match Pipeline::load(&path) {
+        Ok(pipeline) => RecipeInfo {
+            name: pipeline.name.clone().unwrap_or(file_name),
+            description: pipeline.description.clone(),
+            steps: pipeline.steps.iter().map(|step| step.to_string()).collect(),
+            error: None,
+            path,
+        },
+        Err(e) => RecipeInfo {
+            name: file_name,
+            description: None,
+            steps: Vec::new(),
+            error: Some(e.to_string()),
+            path,
+        },
+    }
+}
+
 #[tauri::command]
 async fn pick_file(window: tauri::Window, filter: Option<String>) -> Result<String, String> {
     let mut dialog = rfd::AsyncFileDialog::new().set_parent(&window);
@@ -24,6 +125,9 @@ async fn pick_file(window: tauri::Window, filter: Option<String>) -> Result<Stri
         Some("md") => {
             dialog = dialog.add_filter("Markdown", &["md", "markdown"]);
         }
+        Some("recipe") => {
+            dialog = dialog.add_filter("Recipe", &["toml", "yaml", "yml"]);
+        }
         _ => {
             dialog = dialog.add_filter("PDF", &["pdf"]);
         }
@@ -35,6 +139,24 @@ async fn pick_file(window: tauri::Window, filter: Option<String>) -> Result<Stri
     }
 }
 
+#[tauri::command]
+async fn pick_files(window: tauri::Window, filter: Option<String>) -> Result<Vec<String>, String> {
+    let mut dialog = rfd::AsyncFileDialog::new().set_parent(&window);
+    match filter.as_deref() {
+        Some("md") => {
+            dialog = dialog.add_filter("Markdown", &["md", "markdown"]);
+        }
+        _ => {
+            dialog = dialog.add_filter("PDF", &["pdf"]);
+        }
+    }
+    let handles = dialog.pick_files().await;
+    match handles {
+        Some(h) => Ok(h.iter().map(|f| f.path().display().to_string()).collect()),
+        None => Err("No file selected".to_string()),
+    }
+}
+
 #[tauri::command]
 async fn pick_directory(window: tauri::Window) -> Result<String, String> {
     let handle = rfd::AsyncFileDialog::new()
@@ -54,97 +176,736 @@ fn get_downloads_dir() -> String {
 }
 
 #[tauri::command]
-fn cmd_extract(input: String, pages: String, output_dir: String, output_name: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
-    let name = if output_name.is_empty() {
-        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-        format!("{}_extracted", stem)
+fn cmd_info(input: String) -> Result<PdfInfo, PdfToolError> {
+    inspect_pdf(&PathBuf::from(&input))
+}
+
+#[tauri::command]
+fn cmd_doctor() -> EnvironmentReport {
+    check_environment(&ToolRunner::default())
+}
+
+#[tauri::command]
+fn cmd_list_jobs(jobs: tauri::State<'_, JobQueue>) -> Vec<JobInfo> {
+    jobs.list()
+}
+
+#[tauri::command]
+fn cmd_cancel_job(jobs: tauri::State<'_, JobQueue>, id: JobId) -> Result<(), String> {
+    jobs.cancel(id)
+}
+
+#[tauri::command]
+fn cmd_retry_job(jobs: tauri::State<'_, JobQueue>, id: JobId) -> Result<(), String> {
+    jobs.retry(id)
+}
+
+/// Set the PDF version of the files written by later jobs; empty for each operation's default.
+#[tauri::command]
+fn cmd_output_pdf_version(
+    jobs: tauri::State<'_, JobQueue>,
+    version: String,
+) -> Result<(), PdfToolError> {
+    let version = if version.is_empty() {
+        None
+    } else {
+        Some(PdfVersion::parse(&version)?)
+    };
+    jobs.set_pdf_version(version);
+    Ok(())
+}
+
+#[tauri::command]
+fn cmd_recipes_dir() -> String {
+    recipes_dir().display().to_string()
+}
+
+#[tauri::command]
+fn cmd_list_recipes() -> Vec<RecipeInfo> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(recipes_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "toml" || ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    paths.sort();
+    paths.into_iter().map(recipe_info).collect()
+}
+
+#[tauri::command]
+fn cmd_recipe_info(path: String) -> RecipeInfo {
+    recipe_info(PathBuf::from(path))
+}
+
+#[tauri::command]
+fn cmd_run_pipeline(
+    jobs: tauri::State<'_, JobQueue>,
+    recipe: String,
+    inputs: Vec<String>,
+    output_dir: String,
+    output_name: String,
+) -> Result<Vec<JobId>, PdfToolError> {
+    let pipeline = Pipeline::load(&PathBuf::from(&recipe))?;
+    let template = name_template(&output_name, "{name}_pipeline", inputs.len());
+    Ok(enqueue_each(
+        &jobs,
+        "pipeline",
+        inputs,
+        move |runner, input, num| {
+            pipeline_job(runner, &pipeline, input, num, &output_dir, &template)
+        },
+    ))
+}
+
+fn pipeline_job(
+    runner: &ToolRunner,
+    pipeline: &Pipeline,
+    input: &str,
+    num: usize,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    // A recipe ending with convert writes its images straight into the directory.
+    let output = match pipeline.output_extension() {
+        Some(ext) => dir.join(format!(
+            "{}.{}",
+            render_output_name(template, &input, num),
+            ext
+        )),
+        None => dir,
+    };
+
+    pipeline.run(runner, &input, &output)?;
+
+    Ok(format!("Pipeline finished: {}", output.display()))
+}
+
+#[tauri::command]
+fn cmd_extract(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    pages: String,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}_extracted", inputs.len());
+    enqueue_each(&jobs, "extract", inputs, move |runner, input, num| {
+        extract_job(runner, input, num, &pages, &output_dir, &template)
+    })
+}
+
+fn extract_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    pages: &str,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
     } else {
-        strip_extension(&output_name)
+        PathBuf::from(output_dir)
     };
+    let name = render_output_name(template, &input, num);
     let output = dir.join(format!("{}.pdf", name));
 
-    let page_list = parse_page_range(&pages).map_err(|e| e.to_string())?;
-    extract_pages(&input, &output, &page_list).map_err(|e| e.to_string())?;
+    let total = page_count(&input)?;
+    let page_list = resolve_page_range(pages, total)?;
+    extract_pages(runner, &input, &output, &page_list)?;
 
     Ok(format!("Pages extracted to {}", output.display()))
 }
 
 #[tauri::command]
-fn cmd_extract_text(input: String, output_dir: String, output_name: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
+fn cmd_split(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    split_by: String,
+    value: String,
+    output_dir: String,
+    name_template: String,
+) -> Vec<JobId> {
+    enqueue_each(&jobs, "split", inputs, move |runner, input, _| {
+        split_job(
+            runner,
+            input,
+            &split_by,
+            &value,
+            &output_dir,
+            &name_template,
+        )
+    })
+}
+
+fn split_job(
+    runner: &ToolRunner,
+    input: &str,
+    split_by: &str,
+    value: &str,
+    output_dir: &str,
+    name_template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let template = match strip_extension(name_template) {
+        name if name.is_empty() => DEFAULT_SPLIT_TEMPLATE.to_string(),
+        // A plain name such as "chapter" becomes chapter_1, chapter_2...
+        name if !["{num}", "{start}", "{end}"]
+            .iter()
+            .any(|p| name.contains(p)) =>
+        {
+            format!("{}_{{num}}", name)
+        }
+        name => name,
+    };
+
+    let mode = match split_by {
+        "every" => {
+            let n: u32 = value.trim().parse().map_err(|_| {
+                PdfToolError::InvalidArgument(format!(
+                    "Invalid number of pages: '{}'",
+                    value.trim()
+                ))
+            })?;
+            SplitMode::EveryN(n)
+        }
+        "at" => {
+            let total = page_count(&input)?;
+            SplitMode::AtPages(resolve_page_range(value, total)?)
+        }
+        "size" => {
+            let mb: f64 = value.trim().parse().map_err(|_| {
+                PdfToolError::InvalidArgument(format!("Invalid size: '{}'", value.trim()))
+            })?;
+            if mb <= 0.0 {
+                return Err(PdfToolError::InvalidArgument(
+                    "Maximum size must be greater than 0".to_string(),
+                ));
+            }
+            SplitMode::MaxBytes((mb * 1024.0 * 1024.0) as u64)
+        }
+        other => {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "Unknown split mode '{}'",
+                other
+            )));
+        }
+    };
+
+    let files = split_pdf(runner, &input, &dir, &mode, &template)?;
+
+    Ok(format!(
+        "Split into {} files in {}",
+        files.len(),
+        dir.display()
+    ))
+}
+
+#[tauri::command]
+fn cmd_merge(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    output_dir: String,
+    output_name: String,
+) -> JobId {
+    let label = format!("{} files", inputs.len());
+    jobs.enqueue("merge", label, move |runner| {
+        merge_job(runner, &inputs, &output_dir, &output_name)
+    })
+}
+
+fn merge_job(
+    runner: &ToolRunner,
+    inputs: &[String],
+    output_dir: &str,
+    output_name: &str,
+) -> Result<String, PdfToolError> {
+    let mut merge_inputs = Vec::new();
+    for spec in inputs {
+        merge_inputs.push(
+            parse_merge_input(spec)
+                .map_err(|e| PdfToolError::InvalidArgument(format!("{}: {}", spec, e)))?,
+        );
+    }
+    let first = match merge_inputs.first() {
+        Some(&input) => input.path.clone(),
+        None => {
+            return Err(PdfToolError::InvalidArgument(
+                "Please add at least one PDF file.".to_string(),
+            ));
+        }
+    };
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
     let name = if output_name.is_empty() {
-        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-        stem.to_string()
+        let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+        format!("{}_merged", stem)
+    } else {
+        strip_extension(output_name)
+    };
+    let output = dir.join(format!("{}.pdf", name));
+
+    merge_pdfs(runner, &merge_inputs, &output)?;
+
+    Ok(format!(
+        "Merged {} files into {}",
+        merge_inputs.len(),
+        output.display()
+    ))
+}
+
+#[tauri::command]
+fn cmd_rotate(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    pages: String,
+    degrees: u32,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}_rotated", inputs.len());
+    enqueue_each(&jobs, "rotate", inputs, move |runner, input, num| {
+        rotate_job(runner, input, num, &pages, degrees, &output_dir, &template)
+    })
+}
+
+fn rotate_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    pages: &str,
+    degrees: u32,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let name = render_output_name(template, &input, num);
+    let output = dir.join(format!("{}.pdf", name));
+
+    let page_list = if pages.trim().is_empty() {
+        None
+    } else {
+        let total = page_count(&input)?;
+        Some(resolve_page_range(pages, total)?)
+    };
+    rotate_pages(runner, &input, &output, page_list.as_deref(), degrees)?;
+
+    Ok(format!("Rotated PDF saved to {}", output.display()))
+}
+
+#[tauri::command]
+fn cmd_reorder(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    mode: String,
+    pages: String,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}_reordered", inputs.len());
+    enqueue_each(&jobs, "reorder", inputs, move |runner, input, num| {
+        reorder_job(runner, input, num, &mode, &pages, &output_dir, &template)
+    })
+}
+
+fn reorder_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    mode: &str,
+    pages: &str,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
     } else {
-        strip_extension(&output_name)
+        PathBuf::from(output_dir)
     };
+    let name = render_output_name(template, &input, num);
+    let output = dir.join(format!("{}.pdf", name));
+
+    let total = page_count(&input)?;
+    let mode = match mode {
+        "order" => ReorderMode::Sequence(resolve_page_sequence(pages, total)?),
+        "reverse" => ReorderMode::Reverse,
+        "delete" => ReorderMode::Delete(resolve_page_range(pages, total)?),
+        other => {
+            return Err(PdfToolError::InvalidArgument(format!(
+                "Unknown reorder mode '{}'",
+                other
+            )));
+        }
+    };
+    reorder_pages(runner, &input, &output, &mode)?;
+
+    Ok(format!("Reordered PDF saved to {}", output.display()))
+}
+
+#[tauri::command]
+fn cmd_extract_text(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}", inputs.len());
+    enqueue_each(&jobs, "text", inputs, move |runner, input, num| {
+        extract_text_job(runner, input, num, &output_dir, &template)
+    })
+}
+
+fn extract_text_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let name = render_output_name(template, &input, num);
     let output = dir.join(format!("{}.txt", name));
 
-    extract_text(&input, &output).map_err(|e| e.to_string())?;
+    extract_text(runner, &input, &output)?;
 
     Ok(format!("Text extracted to {}", output.display()))
 }
 
 #[tauri::command]
-fn cmd_compress(input: String, quality: String, output_dir: String, output_name: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
-    let name = if output_name.is_empty() {
-        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-        format!("{}_compressed", stem)
+fn cmd_compress(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    options: CompressOptions,
+    max_size_mb: Option<f64>,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}_compressed", inputs.len());
+    enqueue_each(&jobs, "compress", inputs, move |runner, input, num| {
+        compress_job(
+            runner,
+            input,
+            num,
+            &options,
+            max_size_mb,
+            &output_dir,
+            &template,
+        )
+    })
+}
+
+fn compress_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    options: &CompressOptions,
+    max_size_mb: Option<f64>,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
     } else {
-        strip_extension(&output_name)
+        PathBuf::from(output_dir)
     };
+    let name = render_output_name(template, &input, num);
     let output = dir.join(format!("{}.pdf", name));
 
-    compress_pdf(&input, &output, &quality).map_err(|e| e.to_string())?;
+    if let Some(mb) = max_size_mb {
+        if mb <= 0.0 {
+            return Err(PdfToolError::InvalidArgument(
+                "Maximum size must be greater than 0".to_string(),
+            ));
+        }
+        let fit = compress_to_size(runner, &input, &output, (mb * 1024.0 * 1024.0) as u64)?;
+        return Ok(format!(
+            "Compressed PDF saved to {} ({}, {:.1} MB)",
+            output.display(),
+            fit.options,
+            fit.report.output_size as f64 / (1024.0 * 1024.0)
+        ));
+    }
+    let report = compress_pdf_with(runner, &input, &output, options)?;
 
-    Ok(format!("Compressed PDF saved to {}", output.display()))
+    Ok(format!(
+        "Compressed PDF saved to {}: {}",
+        output.display(),
+        report
+    ))
 }
 
 #[tauri::command]
-fn cmd_convert(input: String, format: String, dpi: u32, output_dir: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
+fn cmd_convert(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    format: String,
+    dpi: u32,
+    pages: String,
+    output_dir: String,
+) -> Vec<JobId> {
+    enqueue_each(&jobs, "convert", inputs, move |runner, input, _| {
+        convert_job(runner, input, &format, dpi, &pages, &output_dir)
+    })
+}
+
+fn convert_job(
+    runner: &ToolRunner,
+    input: &str,
+    format: &str,
+    dpi: u32,
+    pages: &str,
+    output_dir: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let page_list = if pages.trim().is_empty() {
+        None
+    } else {
+        let total = page_count(&input)?;
+        Some(resolve_page_range(pages, total)?)
+    };
 
-    convert_pdf(&input, &dir, &format, dpi).map_err(|e| e.to_string())?;
+    convert_pdf(runner, &input, &dir, format, dpi, page_list.as_deref())?;
 
-    Ok(format!("Converted to {} images in {}", format, dir.display()))
+    Ok(format!(
+        "Converted to {} images in {}",
+        format,
+        dir.display()
+    ))
 }
 
 #[tauri::command]
-fn cmd_md_to_pdf(input: String, output_dir: String, output_name: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
-    let name = if output_name.is_empty() {
-        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-        stem.to_string()
+fn cmd_convert_color(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    mode: String,
+    threshold: u8,
+    output_dir: String,
+    output_name: String,
+) -> Result<Vec<JobId>, PdfToolError> {
+    let mode = ColorMode::parse(&mode, threshold)?;
+    let template = name_template(&output_name, &format!("{{name}}_{}", mode), inputs.len());
+    Ok(enqueue_each(
+        &jobs,
+        "color",
+        inputs,
+        move |runner, input, num| {
+            convert_color_job(runner, input, num, &mode, &output_dir, &template)
+        },
+    ))
+}
+
+fn convert_color_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    mode: &ColorMode,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let name = render_output_name(template, &input, num);
+    let output = dir.join(format!("{}.pdf", name));
+
+    convert_color(runner, &input, &output, mode)?;
+
+    Ok(format!("Converted to {}: {}", mode, output.display()))
+}
+
+#[tauri::command]
+fn cmd_to_pdfa(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    level: String,
+    output_dir: String,
+    output_name: String,
+) -> Result<Vec<JobId>, PdfToolError> {
+    let level = PdfaLevel::parse(&level)?;
+    let template = name_template(&output_name, "{name}_pdfa", inputs.len());
+    Ok(enqueue_each(
+        &jobs,
+        "pdfa",
+        inputs,
+        move |runner, input, num| to_pdfa_job(runner, input, num, level, &output_dir, &template),
+    ))
+}
+
+fn to_pdfa_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    level: PdfaLevel,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let name = render_output_name(template, &input, num);
+    let output = dir.join(format!("{}.pdf", name));
+
+    to_pdfa(runner, &input, &output, level)?;
+
+    Ok(format!("{} saved to {}", level, output.display()))
+}
+
+#[tauri::command]
+fn cmd_set_version(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    version: String,
+    output_dir: String,
+    output_name: String,
+) -> Result<Vec<JobId>, PdfToolError> {
+    let version = PdfVersion::parse(&version)?;
+    let template = name_template(
+        &output_name,
+        &format!("{{name}}_v{}", version),
+        inputs.len(),
+    );
+    Ok(enqueue_each(
+        &jobs,
+        "set-version",
+        inputs,
+        move |runner, input, num| {
+            set_version_job(runner, input, num, version, &output_dir, &template)
+        },
+    ))
+}
+
+fn set_version_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    version: PdfVersion,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
     } else {
-        strip_extension(&output_name)
+        PathBuf::from(output_dir)
     };
+    let name = render_output_name(template, &input, num);
     let output = dir.join(format!("{}.pdf", name));
 
-    md_to_pdf(&input, &output).map_err(|e| e.to_string())?;
+    let warnings = set_pdf_version(runner, &input, &output, version)?;
+
+    let mut message = format!("PDF {} saved to {}", version, output.display());
+    for warning in warnings {
+        message.push_str(&format!(". Warning: {}", warning));
+    }
+    Ok(message)
+}
+
+#[tauri::command]
+fn cmd_md_to_pdf(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}", inputs.len());
+    enqueue_each(&jobs, "md-to-pdf", inputs, move |runner, input, num| {
+        md_to_pdf_job(runner, input, num, &output_dir, &template)
+    })
+}
+
+fn md_to_pdf_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
+    } else {
+        PathBuf::from(output_dir)
+    };
+    let name = render_output_name(template, &input, num);
+    let output = dir.join(format!("{}.pdf", name));
+
+    md_to_pdf(runner, &input, &output)?;
 
     Ok(format!("Markdown converted to PDF: {}", output.display()))
 }
 
 #[tauri::command]
-fn cmd_pdf_to_md(input: String, output_dir: String, output_name: String) -> Result<String, String> {
-    let input = PathBuf::from(&input);
-    let dir = if output_dir.is_empty() { downloads_dir() } else { PathBuf::from(&output_dir) };
-    let name = if output_name.is_empty() {
-        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
-        stem.to_string()
+fn cmd_pdf_to_md(
+    jobs: tauri::State<'_, JobQueue>,
+    inputs: Vec<String>,
+    output_dir: String,
+    output_name: String,
+) -> Vec<JobId> {
+    let template = name_template(&output_name, "{name}", inputs.len());
+    enqueue_each(&jobs, "pdf-to-md", inputs, move |runner, input, num| {
+        pdf_to_md_job(runner, input, num, &output_dir, &template)
+    })
+}
+
+fn pdf_to_md_job(
+    runner: &ToolRunner,
+    input: &str,
+    num: usize,
+    output_dir: &str,
+    template: &str,
+) -> Result<String, PdfToolError> {
+    let input = PathBuf::from(input);
+    let dir = if output_dir.is_empty() {
+        downloads_dir()
     } else {
-        strip_extension(&output_name)
+        PathBuf::from(output_dir)
     };
+    let name = render_output_name(template, &input, num);
     let output = dir.join(format!("{}.md", name));
 
-    pdf_to_md(&input, &output).map_err(|e| e.to_string())?;
+    pdf_to_md(runner, &input, &output)?;
 
     Ok(format!("PDF converted to Markdown: {}", output.display()))
 }
@@ -152,16 +913,41 @@ fn cmd_pdf_to_md(input: String, output_dir: String, output_name: String) -> Resu
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            let handle = app.handle().clone();
+            app.manage(JobQueue::new(MAX_JOBS, move |job| {
+                let _ = handle.emit("job", job);
+            }));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             pick_file,
+            pick_files,
             pick_directory,
             get_downloads_dir,
+            cmd_info,
+            cmd_doctor,
+            cmd_list_jobs,
+            cmd_cancel_job,
+            cmd_retry_job,
+            cmd_output_pdf_version,
             cmd_extract,
+            cmd_split,
+            cmd_merge,
+            cmd_rotate,
+            cmd_reorder,
             cmd_extract_text,
             cmd_compress,
             cmd_convert,
+            cmd_convert_color,
+            cmd_to_pdfa,
+            cmd_set_version,
             cmd_md_to_pdf,
             cmd_pdf_to_md,
+            cmd_recipes_dir,
+            cmd_list_recipes,
+            cmd_recipe_info,
+            cmd_run_pipeline,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
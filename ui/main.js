@@ -5,8 +5,11 @@ const { invoke } = window.__TAURI__.core;
   try {
     const dl = await invoke('get_downloads_dir');
     document.getElementById('extract-dir').value = dl;
+    document.getElementById('merge-dir').value = dl;
+    document.getElementById('pages-dir').value = dl;
     document.getElementById('compress-dir').value = dl;
     document.getElementById('convert-dir').value = dl;
+    document.getElementById('pipeline-dir').value = dl;
   } catch (_) {}
 })();
 
@@ -25,12 +28,15 @@ document.querySelectorAll('.tab').forEach(tab => {
 document.querySelectorAll('.btn-browse').forEach(btn => {
   btn.addEventListener('click', async () => {
     const target = btn.dataset.target;
-    const kind = btn.dataset.pick;  // "file" or "dir"
+    const kind = btn.dataset.pick;  // "file", "files" or "dir"
     try {
       let path;
       if (kind === 'file') {
         const filter = btn.dataset.filter || null;
         path = await invoke('pick_file', { filter });
+      } else if (kind === 'files') {
+        const filter = btn.dataset.filter || null;
+        return setSelectedFiles(target, await invoke('pick_files', { filter }));
       } else if (kind === 'dir') {
         path = await invoke('pick_directory');
       }
@@ -42,24 +48,173 @@ document.querySelectorAll('.btn-browse').forEach(btn => {
   });
 });
 
+// Inputs accepting several files keep the full list in data-paths
+// and show a summary.
+function setSelectedFiles(id, paths) {
+  const el = document.getElementById(id);
+  el.dataset.paths = JSON.stringify(paths);
+  const names = paths.map(p => p.split(/[\\/]/).pop());
+  el.value = paths.length === 1 ? paths[0] : `${paths.length} files: ${names.join(', ')}`;
+}
+
+function selectedFiles(id) {
+  const el = document.getElementById(id);
+  return el.value ? JSON.parse(el.dataset.paths || '[]') : [];
+}
+
+// --- Merge input list ---
+document.getElementById('merge-add-btn').addEventListener('click', async () => {
+  const list = document.getElementById('merge-inputs');
+  try {
+    const paths = await invoke('pick_files');
+    const current = list.value.trim();
+    list.value = (current ? current + '\n' : '') + paths.join('\n');
+  } catch (e) {
+    // User cancelled – ignore
+    console.log('picker cancelled or error:', e);
+  }
+});
+
+document.getElementById('merge-clear-btn').addEventListener('click', () => {
+  document.getElementById('merge-inputs').value = '';
+});
+
+// --- Pipeline recipes ---
+// Recipes saved in the recipes folder are offered as presets; Browse adds any other file.
+const recipes = new Map();  // path -> { path, name, description, steps, error }
+
+(async function loadRecipes() {
+  try {
+    const dir = await invoke('cmd_recipes_dir');
+    document.getElementById('pipeline-recipes-hint').textContent = `(presets: .toml or .yaml files in ${dir})`;
+    for (const recipe of await invoke('cmd_list_recipes')) addRecipe(recipe);
+  } catch (_) {}
+})();
+
+function addRecipe(recipe) {
+  const select = document.getElementById('pipeline-recipe');
+  if (!recipes.has(recipe.path)) {
+    const option = document.createElement('option');
+    option.value = recipe.path;
+    option.textContent = recipe.name;
+    select.append(option);
+  }
+  recipes.set(recipe.path, recipe);
+  select.value = recipe.path;
+  showRecipe();
+}
+
+function showRecipe() {
+  const recipe = recipes.get(document.getElementById('pipeline-recipe').value);
+  const el = document.getElementById('pipeline-steps');
+  if (!recipe) el.textContent = 'No recipe selected.';
+  else if (recipe.error) el.textContent = `Invalid recipe: ${recipe.error}`;
+  else el.textContent = [recipe.description, recipe.steps.join(' → ')].filter(Boolean).join(' — ');
+}
+
+document.getElementById('pipeline-recipe').addEventListener('change', showRecipe);
+
+document.getElementById('pipeline-browse-btn').addEventListener('click', async () => {
+  try {
+    const path = await invoke('pick_file', { filter: 'recipe' });
+    addRecipe(await invoke('cmd_recipe_info', { path }));
+  } catch (e) {
+    // User cancelled – ignore
+    console.log('picker cancelled or error:', e);
+  }
+});
+
 // --- Extract mode toggle ---
 document.getElementById('extract-mode').addEventListener('change', (e) => {
   const mode = e.target.value;
   const pagesField = document.getElementById('extract-pages-field');
+  const splitOptions = document.getElementById('extract-split-options');
   const nameHint = document.getElementById('extract-name-hint');
+  const nameInput = document.getElementById('extract-name');
   const actionBtn = document.querySelector('#extract .btn-action');
 
   if (mode === 'text') {
     pagesField.style.display = 'none';
-    nameHint.textContent = '(optional — .txt added automatically)';
+    splitOptions.style.display = 'none';
+    nameHint.textContent = '(optional — .txt added automatically, {name} = input file name)';
+    nameInput.placeholder = 'Default: input_extracted';
     actionBtn.textContent = 'Extract Text';
+  } else if (mode === 'split') {
+    pagesField.style.display = 'none';
+    splitOptions.style.display = '';
+    nameHint.textContent = '(template — {name}, {num}, {start}, {end})';
+    nameInput.placeholder = 'Default: {name}_part{num}';
+    actionBtn.textContent = 'Split PDF';
   } else {
     pagesField.style.display = '';
-    nameHint.textContent = '(optional — .pdf added automatically)';
+    splitOptions.style.display = 'none';
+    nameHint.textContent = '(optional — .pdf added automatically, {name} = input file name)';
+    nameInput.placeholder = 'Default: input_extracted';
     actionBtn.textContent = 'Extract Pages';
   }
 });
 
+// --- Split mode toggle ---
+document.getElementById('split-by').addEventListener('change', (e) => {
+  const label = document.getElementById('split-value-label');
+  const value = document.getElementById('split-value');
+
+  if (e.target.value === 'at') {
+    label.textContent = 'Start a new file at';
+    value.placeholder = '5,12,30';
+  } else if (e.target.value === 'size') {
+    label.textContent = 'Max size per file (MB)';
+    value.placeholder = '10';
+  } else {
+    label.textContent = 'Pages per file';
+    value.placeholder = '10';
+  }
+});
+
+// --- Pages mode toggle ---
+document.getElementById('pages-mode').addEventListener('change', (e) => {
+  const mode = e.target.value;
+  const rangeField = document.getElementById('pages-range-field');
+  const rangeHint = document.getElementById('pages-range-hint');
+  const rangeInput = document.getElementById('pages-range');
+  const degreesField = document.getElementById('pages-degrees-field');
+  const nameInput = document.getElementById('pages-name');
+  const actionBtn = document.querySelector('#pages .btn-action');
+
+  rangeField.style.display = mode === 'reverse' ? 'none' : '';
+  degreesField.style.display = mode === 'rotate' ? '' : 'none';
+
+  if (mode === 'rotate') {
+    rangeHint.textContent = 'blank = all pages';
+    rangeInput.placeholder = '1,3-5';
+    nameInput.placeholder = 'Default: input_rotated';
+    actionBtn.textContent = 'Rotate Pages';
+  } else if (mode === 'order') {
+    rangeHint.textContent = 'new order, pages may repeat';
+    rangeInput.placeholder = '5,1-4,5';
+    nameInput.placeholder = 'Default: input_reordered';
+    actionBtn.textContent = 'Reorder Pages';
+  } else if (mode === 'reverse') {
+    nameInput.placeholder = 'Default: input_reordered';
+    actionBtn.textContent = 'Reverse Pages';
+  } else {
+    rangeHint.textContent = 'pages to remove';
+    rangeInput.placeholder = '2,4-6';
+    nameInput.placeholder = 'Default: input_reordered';
+    actionBtn.textContent = 'Delete Pages';
+  }
+});
+
+// --- Output PDF version ---
+// Applies to the jobs started afterwards; the Compress tab's own setting wins for compression.
+document.getElementById('output-pdf-version').addEventListener('change', async (e) => {
+  try {
+    await invoke('cmd_output_pdf_version', { version: e.target.value });
+  } catch (err) {
+    showError(err);
+  }
+});
+
 // --- Convert mode toggle ---
 document.getElementById('convert-mode').addEventListener('change', (e) => {
   const mode = e.target.value;
@@ -70,11 +225,14 @@ document.getElementById('convert-mode').addEventListener('change', (e) => {
   const browseBtn = document.getElementById('convert-browse-btn');
   const actionBtn = document.querySelector('#convert .btn-action');
 
+  document.getElementById('convert-color-options').style.display = mode === 'color' ? '' : 'none';
+  document.getElementById('convert-pdfa-options').style.display = mode === 'pdfa' ? '' : 'none';
+  document.getElementById('convert-version-options').style.display = mode === 'version' ? '' : 'none';
   if (mode === 'md-to-pdf') {
     imageOptions.style.display = 'none';
     nameField.style.display = '';
     inputLabel.textContent = 'Input Markdown';
-    inputEl.placeholder = 'Select a Markdown file...';
+    inputEl.placeholder = 'Select one or more Markdown files...';
     inputEl.value = '';
     browseBtn.dataset.filter = 'md';
     actionBtn.textContent = 'Convert to PDF';
@@ -82,15 +240,23 @@ document.getElementById('convert-mode').addEventListener('change', (e) => {
     imageOptions.style.display = 'none';
     nameField.style.display = '';
     inputLabel.textContent = 'Input PDF';
-    inputEl.placeholder = 'Select a PDF file...';
+    inputEl.placeholder = 'Select one or more PDF files...';
     inputEl.value = '';
     delete browseBtn.dataset.filter;
     actionBtn.textContent = 'Convert to Markdown';
+  } else if (mode === 'color' || mode === 'pdfa' || mode === 'version') {
+    imageOptions.style.display = 'none';
+    nameField.style.display = '';
+    inputLabel.textContent = 'Input PDF';
+    inputEl.placeholder = 'Select one or more PDF files...';
+    inputEl.value = '';
+    delete browseBtn.dataset.filter;
+    actionBtn.textContent = { color: 'Convert Colours', pdfa: 'Convert to PDF/A', version: 'Change Version' }[mode];
   } else {
     imageOptions.style.display = '';
     nameField.style.display = 'none';
     inputLabel.textContent = 'Input PDF';
-    inputEl.placeholder = 'Select a PDF file...';
+    inputEl.placeholder = 'Select one or more PDF files...';
     inputEl.value = '';
     delete browseBtn.dataset.filter;
     actionBtn.textContent = 'Convert to Images';
@@ -104,15 +270,28 @@ document.querySelectorAll('.btn-action').forEach(btn => {
     if (action === 'extract') {
       const mode = document.getElementById('extract-mode').value;
       if (mode === 'text') runExtractText();
+      else if (mode === 'split') runSplit();
       else runExtract();
     }
+    else if (action === 'merge') runMerge();
+    else if (action === 'pages') {
+      const mode = document.getElementById('pages-mode').value;
+      if (mode === 'rotate') runRotate();
+      else runReorder(mode);
+    }
     else if (action === 'compress') runCompress();
     else if (action === 'convert') {
       const mode = document.getElementById('convert-mode').value;
       if (mode === 'md-to-pdf') runMdToPdf();
       else if (mode === 'pdf-to-md') runPdfToMd();
+      else if (mode === 'color') runConvertColor();
+      else if (mode === 'pdfa') runPdfa();
+      else if (mode === 'version') runSetVersion();
       else runConvert();
     }
+    else if (action === 'pipeline') runPipeline();
+    else if (action === 'info') runInfo();
+    else if (action === 'doctor') runDoctor();
   });
 });
 
@@ -123,6 +302,20 @@ function showStatus(message, type) {
   el.className = `status ${type}`;
 }
 
+// Commands reject with a PdfToolError ({ kind, message, code, stderr }).
+// When an external tool failed, its output is shown under the message.
+function showError(e) {
+  if (typeof e === 'string') return showStatus(e, 'error');
+  if (e.kind === 'cancelled') return showStatus('Cancelled — no files were written.', 'error');
+  showStatus(e.message, 'error');
+  if (e.stderr) {
+    const details = document.createElement('pre');
+    details.className = 'status-details';
+    details.textContent = e.stderr;
+    document.getElementById('status').append(details);
+  }
+}
+
 function hideStatus() {
   document.getElementById('status').className = 'status hidden';
 }
@@ -132,122 +325,561 @@ function setLoading(btn) {
   btn.dataset.originalText = btn.textContent;
   btn.textContent = 'Processing...';
   showStatus('Processing...', 'loading');
+  document.getElementById('cancel-btn').classList.remove('hidden');
 }
 
 function clearLoading(btn) {
   btn.disabled = false;
   btn.textContent = btn.dataset.originalText;
+  document.getElementById('cancel-btn').classList.add('hidden');
+}
+
+// Stops the jobs the status bar follows; they end as "cancelled"
+// after its half-written output is removed.
+document.getElementById('cancel-btn').addEventListener('click', () => {
+  for (const id of currentJobs) invoke('cmd_cancel_job', { id });
+});
+
+// --- Jobs ---
+// Operations run as background jobs: commands return a job id at once and every
+// change is sent as a `job` event ({ id, kind, label, status, progress, message, error }).
+const jobs = new Map();     // id -> latest job info
+const waiting = new Map();  // id -> { resolve, reject } of a runJob call
+let currentJobs = [];      // jobs shown in the status bar
+
+window.__TAURI__.event.listen('job', ({ payload }) => {
+  jobs.set(payload.id, payload);
+  if (currentJobs.includes(payload.id)) showJobProgress(payload);
+  const waiter = waiting.get(payload.id);
+  if (waiter) settle(payload, waiter);
+  renderJobs();
+});
+
+(async function loadJobs() {
+  try {
+    for (const job of await invoke('cmd_list_jobs')) jobs.set(job.id, job);
+    renderJobs();
+  } catch (_) {}
+})();
+
+// Enqueue an operation and wait for it like a direct command call:
+// resolves with the success message, rejects with the PdfToolError.
+// Commands taking several inputs enqueue one job per file; the result then
+// counts the files and the Jobs tab has the details.
+async function runJob(command, args) {
+  const ids = [].concat(await invoke(command, args));
+  currentJobs = ids;
+  try {
+    const results = await Promise.allSettled(ids.map(waitForJob));
+    if (ids.length === 1) {
+      if (results[0].status === 'rejected') throw results[0].reason;
+      return results[0].value;
+    }
+    const failed = results.filter(r => r.status === 'rejected').length;
+    if (failed) throw `${failed} of ${ids.length} files failed — see the Jobs tab.`;
+    return `${ids.length} files processed — see the Jobs tab for the output files.`;
+  } finally {
+    if (currentJobs === ids) currentJobs = [];
+  }
+}
+
+function waitForJob(id) {
+  return new Promise((resolve, reject) => {
+    const waiter = { resolve, reject };
+    waiting.set(id, waiter);
+    // The job may have finished before invoke returned
+    if (jobs.has(id)) settle(jobs.get(id), waiter);
+  });
+}
+
+function settle(job, waiter) {
+  if (job.status === 'done') waiter.resolve(job.message);
+  else if (job.status === 'failed') waiter.reject(job.error);
+  else if (job.status === 'cancelled') waiter.reject({ kind: 'cancelled' });
+  else return;
+  waiting.delete(job.id);
+}
+
+// Long operations report { done, total } page counts while they run.
+function showJobProgress(job) {
+  const el = document.getElementById('status');
+  if (!el.classList.contains('loading')) return;
+  if (currentJobs.length > 1) {
+    const done = currentJobs.filter(id => ['done', 'failed', 'cancelled'].includes(jobs.get(id)?.status)).length;
+    el.textContent = `Processing... ${done} of ${currentJobs.length} files done`;
+    return el.append(progressBar({ done, total: currentJobs.length }));
+  }
+  if (job.status === 'queued') return (el.textContent = 'Waiting for other jobs to finish...');
+  if (!job.progress || !job.progress.total) return (el.textContent = 'Processing...');
+  el.textContent = `Processing... page ${job.progress.done} of ${job.progress.total}`;
+  el.append(progressBar(job.progress));
+}
+
+function progressBar(progress) {
+  const percent = Math.min(100, Math.round(progress.done / progress.total * 100));
+  const bar = document.createElement('div');
+  bar.className = 'progress';
+  const fill = document.createElement('div');
+  fill.className = 'progress-fill';
+  fill.style.width = `${percent}%`;
+  bar.append(fill);
+  return bar;
+}
+
+function renderJobs() {
+  const table = document.createElement('table');
+  table.className = 'info-table';
+  for (const job of [...jobs.values()].reverse()) {
+    const tr = document.createElement('tr');
+    const th = document.createElement('th');
+    th.textContent = `${job.kind}: ${job.label}`;
+
+    const td = document.createElement('td');
+    let state = job.status.charAt(0).toUpperCase() + job.status.slice(1);
+    if (job.status === 'done') state = job.message;
+    else if (job.status === 'failed') state = `Failed: ${job.error.message}`;
+    else if (job.status === 'running' && job.progress && job.progress.total) {
+      state = `Page ${job.progress.done} of ${job.progress.total}`;
+    }
+    td.append(state);
+    if (job.status === 'running' && job.progress && job.progress.total) td.append(progressBar(job.progress));
+
+    const action = document.createElement('td');
+    if (job.status === 'queued' || job.status === 'running') {
+      action.append(jobButton('Cancel', () => invoke('cmd_cancel_job', { id: job.id })));
+    } else if (job.status === 'failed' || job.status === 'cancelled') {
+      action.append(jobButton('Retry', () => invoke('cmd_retry_job', { id: job.id })));
+    }
+
+    tr.append(th, td, action);
+    table.append(tr);
+  }
+  document.getElementById('jobs-output').replaceChildren(table);
+}
+
+function jobButton(text, onClick) {
+  const btn = document.createElement('button');
+  btn.className = 'btn-browse';
+  btn.textContent = text;
+  btn.addEventListener('click', () => onClick().catch(showError));
+  return btn;
 }
 
 // --- Commands ---
 async function runExtract() {
-  const input = document.getElementById('extract-input').value;
+  const inputs = selectedFiles('extract-input');
   const pages = document.getElementById('extract-pages').value;
   const dir = document.getElementById('extract-dir').value;
   const name = document.getElementById('extract-name').value.trim();
   const btn = document.querySelector('#extract .btn-action');
 
-  if (!input) return showStatus('Please select an input PDF file.', 'error');
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
   if (!pages) return showStatus('Please enter a page range.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_extract', { input, pages, outputDir: dir, outputName: name });
+    const result = await runJob('cmd_extract', { inputs, pages, outputDir: dir, outputName: name });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runSplit() {
+  const inputs = selectedFiles('extract-input');
+  const splitBy = document.getElementById('split-by').value;
+  const value = document.getElementById('split-value').value.trim();
+  const dir = document.getElementById('extract-dir').value;
+  const nameTemplate = document.getElementById('extract-name').value.trim();
+  const btn = document.querySelector('#extract .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+  if (!value) return showStatus('Please enter where to split.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_split', { inputs, splitBy, value, outputDir: dir, nameTemplate });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
   }
   clearLoading(btn);
 }
 
 async function runExtractText() {
-  const input = document.getElementById('extract-input').value;
+  const inputs = selectedFiles('extract-input');
   const dir = document.getElementById('extract-dir').value;
   const name = document.getElementById('extract-name').value.trim();
   const btn = document.querySelector('#extract .btn-action');
 
-  if (!input) return showStatus('Please select an input PDF file.', 'error');
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_extract_text', { input, outputDir: dir, outputName: name });
+    const result = await runJob('cmd_extract_text', { inputs, outputDir: dir, outputName: name });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
   }
   clearLoading(btn);
 }
 
+async function runMerge() {
+  const inputs = document.getElementById('merge-inputs').value
+    .split('\n')
+    .map(line => line.trim())
+    .filter(line => line);
+  const dir = document.getElementById('merge-dir').value;
+  const name = document.getElementById('merge-name').value.trim();
+  const btn = document.querySelector('#merge .btn-action');
+
+  if (inputs.length === 0) return showStatus('Please add at least one PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_merge', { inputs, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runRotate() {
+  const inputs = selectedFiles('pages-input');
+  const pages = document.getElementById('pages-range').value.trim();
+  const degrees = parseInt(document.getElementById('pages-degrees').value);
+  const dir = document.getElementById('pages-dir').value;
+  const name = document.getElementById('pages-name').value.trim();
+  const btn = document.querySelector('#pages .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_rotate', { inputs, pages, degrees, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runReorder(mode) {
+  const inputs = selectedFiles('pages-input');
+  const pages = document.getElementById('pages-range').value.trim();
+  const dir = document.getElementById('pages-dir').value;
+  const name = document.getElementById('pages-name').value.trim();
+  const btn = document.querySelector('#pages .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+  if (mode !== 'reverse' && !pages) return showStatus('Please enter the pages.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_reorder', { inputs, mode, pages, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+// Quality preset plus the Advanced fields; blank fields are sent as null
+// so the preset's value is kept.
+function compressOptions() {
+  const value = id => document.getElementById(id).value.trim();
+  const number = id => value(id) === '' ? null : Number(value(id));
+  const bool = id => value(id) === '' ? null : value(id) === 'true';
+  return {
+    preset: value('compress-quality'),
+    color_dpi: number('compress-color-dpi'),
+    gray_dpi: number('compress-gray-dpi'),
+    mono_dpi: number('compress-mono-dpi'),
+    jpeg_quality: number('compress-jpeg-quality'),
+    downsample_threshold: number('compress-threshold'),
+    embed_fonts: bool('compress-embed-fonts'),
+    subset_fonts: bool('compress-subset-fonts'),
+    pdf_version: value('compress-pdf-version') || null,
+    keep_original: document.getElementById('compress-keep-original').checked,
+    linearize: document.getElementById('compress-linearize').checked,
+  };
+}
+
 async function runCompress() {
-  const input = document.getElementById('compress-input').value;
-  const quality = document.getElementById('compress-quality').value;
+  const inputs = selectedFiles('compress-input');
+  const options = compressOptions();
+  const maxSize = document.getElementById('compress-max-size').value.trim();
+  const maxSizeMb = maxSize ? Number(maxSize) : null;
   const dir = document.getElementById('compress-dir').value;
   const name = document.getElementById('compress-name').value.trim();
   const btn = document.querySelector('#compress .btn-action');
 
-  if (!input) return showStatus('Please select an input PDF file.', 'error');
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_compress', { input, quality, outputDir: dir, outputName: name });
+    const result = await runJob('cmd_compress', { inputs, options, maxSizeMb, outputDir: dir, outputName: name });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
   }
   clearLoading(btn);
 }
 
 async function runConvert() {
-  const input = document.getElementById('convert-input').value;
+  const inputs = selectedFiles('convert-input');
   const format = document.getElementById('convert-format').value;
   const dpi = parseInt(document.getElementById('convert-dpi').value) || 300;
+  const pages = document.getElementById('convert-pages').value.trim();
   const dir = document.getElementById('convert-dir').value;
   const btn = document.querySelector('#convert .btn-action');
 
-  if (!input) return showStatus('Please select an input PDF file.', 'error');
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_convert', { input, format, dpi, outputDir: dir });
+    const result = await runJob('cmd_convert', { inputs, format, dpi, pages, outputDir: dir });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
   }
   clearLoading(btn);
 }
 
 async function runPdfToMd() {
-  const input = document.getElementById('convert-input').value;
+  const inputs = selectedFiles('convert-input');
   const dir = document.getElementById('convert-dir').value;
   const name = document.getElementById('convert-name').value.trim();
   const btn = document.querySelector('#convert .btn-action');
 
-  if (!input) return showStatus('Please select a PDF file.', 'error');
+  if (!inputs.length) return showStatus('Please select a PDF file.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_pdf_to_md', { input, outputDir: dir, outputName: name });
+    const result = await runJob('cmd_pdf_to_md', { inputs, outputDir: dir, outputName: name });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
   }
   clearLoading(btn);
 }
 
 async function runMdToPdf() {
-  const input = document.getElementById('convert-input').value;
+  const inputs = selectedFiles('convert-input');
   const dir = document.getElementById('convert-dir').value;
   const name = document.getElementById('convert-name').value.trim();
   const btn = document.querySelector('#convert .btn-action');
 
-  if (!input) return showStatus('Please select a Markdown file.', 'error');
+  if (!inputs.length) return showStatus('Please select a Markdown file.', 'error');
 
   setLoading(btn);
   try {
-    const result = await invoke('cmd_md_to_pdf', { input, outputDir: dir, outputName: name });
+    const result = await runJob('cmd_md_to_pdf', { inputs, outputDir: dir, outputName: name });
     showStatus(result, 'success');
   } catch (e) {
-    showStatus(e, 'error');
+    showError(e);
   }
   clearLoading(btn);
 }
+
+async function runConvertColor() {
+  const inputs = selectedFiles('convert-input');
+  const mode = document.getElementById('convert-color').value;
+  const threshold = parseInt(document.getElementById('convert-threshold').value) || 50;
+  const dir = document.getElementById('convert-dir').value;
+  const name = document.getElementById('convert-name').value.trim();
+  const btn = document.querySelector('#convert .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_convert_color', { inputs, mode, threshold, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runPdfa() {
+  const inputs = selectedFiles('convert-input');
+  const level = document.getElementById('convert-pdfa-level').value;
+  const dir = document.getElementById('convert-dir').value;
+  const name = document.getElementById('convert-name').value.trim();
+  const btn = document.querySelector('#convert .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_to_pdfa', { inputs, level, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runSetVersion() {
+  const inputs = selectedFiles('convert-input');
+  const version = document.getElementById('convert-version').value;
+  const dir = document.getElementById('convert-dir').value;
+  const name = document.getElementById('convert-name').value.trim();
+  const btn = document.querySelector('#convert .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_set_version', { inputs, version, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runPipeline() {
+  const recipe = document.getElementById('pipeline-recipe').value;
+  const inputs = selectedFiles('pipeline-input');
+  const dir = document.getElementById('pipeline-dir').value;
+  const name = document.getElementById('pipeline-name').value.trim();
+  const btn = document.querySelector('#pipeline .btn-action');
+
+  if (!recipe) return showStatus('Please choose a recipe.', 'error');
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  try {
+    const result = await runJob('cmd_run_pipeline', { recipe, inputs, outputDir: dir, outputName: name });
+    showStatus(result, 'success');
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+async function runInfo() {
+  const input = document.getElementById('info-input').value;
+  const output = document.getElementById('info-output');
+  const btn = document.querySelector('#info .btn-action');
+
+  if (!inputs.length) return showStatus('Please select an input PDF file.', 'error');
+
+  setLoading(btn);
+  output.classList.add('hidden');
+  try {
+    const info = await invoke('cmd_info', { input });
+    renderInfo(output, info);
+    output.classList.remove('hidden');
+    hideStatus();
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+function formatSize(bytes) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} bytes`;
+}
+
+// Group consecutive pages that share a size and rotation, e.g. "1-12: 595 x 842 pt".
+function pageSizeLines(pages) {
+  const lines = [];
+  let start = 0;
+  while (start < pages.length) {
+    const p = pages[start];
+    let end = start;
+    while (end + 1 < pages.length
+      && pages[end + 1].width === p.width
+      && pages[end + 1].height === p.height
+      && pages[end + 1].rotation === p.rotation) {
+      end++;
+    }
+    const range = start === end ? `${p.number}` : `${p.number}-${pages[end].number}`;
+    const mm = `${Math.round(p.width * 25.4 / 72)} x ${Math.round(p.height * 25.4 / 72)} mm`;
+    const rotation = p.rotation ? `, rotated ${p.rotation}°` : '';
+    lines.push(`${range}: ${Math.round(p.width)} x ${Math.round(p.height)} pt (${mm})${rotation}`);
+    start = end + 1;
+  }
+  return lines;
+}
+
+function renderInfo(container, info) {
+  const rows = [
+    ['Size', formatSize(info.file_size)],
+    ['Version', `PDF ${info.version}`],
+    ['Pages', `${info.page_count}`],
+    ['Title', info.title],
+    ['Author', info.author],
+    ['Creator', info.creator],
+    ['Producer', info.producer],
+    ['Encrypted', info.encrypted ? 'Yes' : 'No'],
+    ['Images', `${info.image_count}`],
+    ['Linearized', info.linearized ? 'Yes' : 'No'],
+    ['Page sizes', pageSizeLines(info.pages).join('\n')],
+    ['Fonts', info.fonts.length
+      ? info.fonts.map(f => `${f.name} (${f.kind}, ${f.embedded ? 'embedded' : 'not embedded'})`).join('\n')
+      : 'None'],
+  ];
+
+  const table = document.createElement('table');
+  table.className = 'info-table';
+  for (const [label, value] of rows) {
+    if (value === null || value === undefined) continue;
+    table.append(tableRow(label, value));
+  }
+  container.replaceChildren(table);
+}
+
+async function runDoctor() {
+  const output = document.getElementById('doctor-output');
+  const btn = document.querySelector('#doctor .btn-action');
+
+  setLoading(btn);
+  output.classList.add('hidden');
+  try {
+    const report = await invoke('cmd_doctor');
+    renderDoctor(output, report);
+    output.classList.remove('hidden');
+    hideStatus();
+  } catch (e) {
+    showError(e);
+  }
+  clearLoading(btn);
+}
+
+function renderDoctor(container, report) {
+  const tools = document.createElement('table');
+  tools.className = 'info-table';
+  for (const tool of report.tools) {
+    let state = 'Not found';
+    if (tool.version) state = tool.version;
+    else if (tool.error) state = `Not working: ${tool.error}`;
+    if (tool.path) state += `\n${tool.path} (${tool.source})`;
+    tools.append(tableRow(tool.name, state));
+  }
+
+  const features = document.createElement('table');
+  features.className = 'info-table';
+  for (const feature of report.features) {
+    features.append(tableRow(feature.name, feature.available ? 'Available' : `Unavailable — ${feature.reason}`));
+  }
+
+  container.replaceChildren(tools, features);
+}
+
+function tableRow(label, value) {
+  const tr = document.createElement('tr');
+  const th = document.createElement('th');
+  const td = document.createElement('td');
+  th.textContent = label;
+  td.textContent = value;
+  tr.append(th, td);
+  return tr;
+}